@@ -1,26 +1,52 @@
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver};
-use std::time::Duration;
-use anyhow::{anyhow, Error};
+use std::time::{Duration, SystemTime};
+use anyhow::Error;
 use crossterm::event::KeyCode;
 use native_dialog::FileDialog;
 use notify::{PollWatcher, RecursiveMode, Watcher, Config, Event};
+use crate::bug_report;
 use crate::controls::stateful_list::StatefulList;
 use crate::controls::stateful_tabs::TabsState;
+use crate::controls::stateful_tree::StatefulTree;
+use crate::corpus::{Corpus, CrashOrigin, Facet};
+use crate::crash_export::{load_metrics, CrashExport};
+use crate::exporters::ExportFormat;
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::graphics_settings::GraphicsSettings;
+use crate::minimap::MinimapData;
+use crate::runtime_id::HashList;
+use crate::rules::{Rule, RuleSet};
+use crate::scenes::SceneTable;
 use crate::tabs::callstack_tab::CallstackTab;
+use crate::tabs::corpus_tab::CorpusTab;
 use crate::tabs::exception_tab::ExceptionTab;
 use crate::tabs::gameplay_tab::GameplayTab;
 use crate::tabs::gpu_tab::GpuTab;
 use crate::tabs::module_tab::ModuleTab;
 use crate::tabs::settings_tab::SettingsTab;
+use crate::tabs::statistics_tab::StatisticsTab;
 use crate::tabs::system_tab::SystemTab;
 use crate::tabs::vr_tab::VrTab;
+use crate::timing::crash_file_time;
+
+//position of the corpus tab, other tabs send the user there
+const CORPUS_TAB: usize = 8;
 
 pub struct DataStore {
     pub should_quit: bool,
     pub quit_msg: String,
     pub enhanced_graphics: bool,
     pub should_live_update: bool,
+    //shown in the header until it is replaced, e.g. the result of a save
+    pub status_msg: Option<String>,
+    pub export_format: ExportFormat,
+
+    //lookup table for the scene resource paths
+    pub scenes: SceneTable,
+    pub rules: RuleSet,
+    pub hash_list: Option<HashList>,
+    pub minimaps: MinimapData,
 
     //modules tab storage
     pub modules: StatefulList<String>,
@@ -28,8 +54,26 @@ pub struct DataStore {
     //callstack tab storage
     pub callstack: StatefulList<String>,
 
+    //exception tab storage, the rules the loaded crash matched
+    pub diagnoses: Vec<Rule>,
+
+    //gpu tab storage
+    pub gpu_report: StatefulTree,
+
+    //settings tab storage
+    pub previous_settings: Option<GraphicsSettings>,
+
+    //corpus and statistics tab storage
+    pub corpus: Corpus,
+    //set by the statistics tab to show only some crashes in the corpus tab
+    pub corpus_facet: Option<Facet>,
+    //set by a tab to replace the loaded crash
+    pub open_request: Option<CrashOrigin>,
+
     //watched file props
     pub path: String,
+    //modification time of the loaded crash file, used to reconstruct the crash time
+    pub crash_time: Option<SystemTime>,
     receiver: Receiver<notify::Result<Event>>,
     watcher: PollWatcher,
 
@@ -63,15 +107,30 @@ impl<'a> App<'a> {
                     Box::new(ModuleTab::new()),
                     Box::new(CallstackTab::new()),
                     Box::new(ExceptionTab::new()),
+                    Box::new(CorpusTab::new()),
+                    Box::new(StatisticsTab::new()),
                 ]),
             data: DataStore {
                 should_quit: false,
                 quit_msg: String::new(),
+                status_msg: None,
+                export_format: ExportFormat::Json,
                 should_live_update: true,
 
+                scenes: SceneTable::load_default(),
+                rules: RuleSet::load_default(),
+                hash_list: HashList::load_default(),
+                minimaps: MinimapData::load_default(),
                 modules: StatefulList::with_items(vec![]),
                 callstack: StatefulList::with_items(vec![]),
+                diagnoses: vec![],
+                gpu_report: StatefulTree::with_items(vec![]),
+                previous_settings: None,
+                corpus: Corpus::new(),
+                corpus_facet: None,
+                open_request: None,
                 path: String::new(),
+                crash_time: None,
                 receiver,
                 watcher,
 
@@ -82,8 +141,12 @@ impl<'a> App<'a> {
     }
 
     pub fn on_load(&mut self) {
+        //a corpus is browsed first instead of asking for a single file
         if self.data.path.is_empty() {
-            self.import_metrics();
+            match self.data.corpus.source {
+                Some(_) => self.tabs.index = CORPUS_TAB,
+                None => self.import_metrics(),
+            }
         }
 
         for tab in self.tabs.tabs.iter_mut() {
@@ -91,9 +154,28 @@ impl<'a> App<'a> {
         }
     }
 
+    pub fn captures_input(&mut self) -> bool {
+        self.tabs.current().map(|tab| tab.captures_input()).unwrap_or(false)
+    }
+
     pub fn on_key(&mut self, key: KeyCode) {
+        if self.captures_input() {
+            self.forward_key(key);
+            return;
+        }
         match key {
-            KeyCode::Char(dig) if dig.is_numeric() => self.tabs.index = u32::clamp(dig.to_digit(0x10).unwrap_or(0), 1, self.tabs.tabs.len() as u32) as usize - 1,
+            //0 is the tenth tab, like on the keyboard
+            KeyCode::Char(dig) if dig.is_numeric() => {
+                let tab = match dig.to_digit(10).unwrap_or(1) {
+                    0 => 10,
+                    tab => tab,
+                };
+                self.tabs.index = u32::clamp(tab, 1, self.tabs.tabs.len() as u32) as usize - 1;
+            }
+            //crashes from a crash database have no file to watch
+            KeyCode::Char('l') if self.data.path.is_empty() => {
+                self.data.status_msg = Some("Live update needs an imported file, this crash has none".to_string());
+            }
             KeyCode::Char('l') => {
                 self.data.should_live_update = !self.data.should_live_update;
                 if self.data.should_live_update {
@@ -108,18 +190,67 @@ impl<'a> App<'a> {
                 }
             }
             KeyCode::Char('s') => {
-                self.save_metrics();
+                self.data.status_msg = Some(match self.save_metrics() {
+                    Ok(Some(path)) => format!("Saved {}", path.display()),
+                    Ok(None) => return,
+                    Err(e) => format!("Failed to save: {e}"),
+                });
+            }
+            //R asks for the template first
+            KeyCode::Char(key @ ('r' | 'R')) => {
+                self.data.status_msg = Some(match self.save_report(key == 'R') {
+                    Ok(Some(path)) => format!("Wrote bug report to {}", path.display()),
+                    Ok(None) => return,
+                    Err(e) => format!("Failed to write bug report: {e}"),
+                });
+            }
+            KeyCode::Char('S') => {
+                self.data.export_format = self.data.export_format.next();
+                self.data.status_msg = Some(format!("Saving as {}", self.data.export_format.name()));
             }
             KeyCode::Char('i') => {
                 self.import_metrics();
             }
-            _ => if let Some(tab) = self.tabs.current() {
-                tab.on_key(&mut self.data, key);
-            },
+            _ => self.forward_key(key),
+        }
+    }
+
+    fn forward_key(&mut self, key: KeyCode) {
+        if let Some(tab) = self.tabs.current() {
+            tab.on_key(&mut self.data, key);
+        }
+        if self.data.corpus_facet.is_some() {
+            self.tabs.index = CORPUS_TAB;
+        }
+        if let Some(origin) = self.data.open_request.take() {
+            self.data.status_msg = Some(match self.open_crash(&origin) {
+                Ok(_) => format!("Opened {origin}"),
+                Err(e) => format!("Failed to open {origin}: {e}"),
+            });
+        }
+    }
+
+    //files are watched like an imported file, database entries don't change so live refresh is turned off
+    fn open_crash(&mut self, origin: &CrashOrigin) -> Result<(), Error> {
+        let (metrics, crash_time) = origin.load()?;
+        let _ = self.data.watcher.unwatch(Path::new(&self.data.path));
+        match origin {
+            CrashOrigin::File(path) => {
+                self.data.watcher.watch(path, RecursiveMode::NonRecursive)?;
+                self.data.path = path.to_string_lossy().to_string();
+            }
+            CrashOrigin::Database { .. } => {
+                self.data.path.clear();
+                self.data.should_live_update = false;
+            }
         }
+        self.set_metrics(metrics, crash_time);
+        self.tabs.index = 0;
+        Ok(())
     }
 
     pub fn on_tick(&mut self) {
+        self.data.corpus.poll(&self.data.scenes);
         while let Ok(event) = self.data.receiver.recv_timeout(Duration::from_millis(10)) {
             if let Ok(event) = event {
                 if let Some(path) = event.paths.last() {
@@ -135,7 +266,7 @@ impl<'a> App<'a> {
     fn import_metrics(&mut self) {
         let path = FileDialog::new()
             .set_location("~/AppData")
-            .add_filter("crash_metrics", &["dat", "json"])
+            .add_filter("crash_metrics", &["dat", "json", "yaml", "yml", "toml", "cbor", "msgpack"])
             .show_open_single_file()
             .unwrap();
 
@@ -159,37 +290,77 @@ impl<'a> App<'a> {
     }
 
     pub(crate) fn update_metrics(&mut self, path: &str) -> Result<(), Error> {
-        if path.ends_with(".json") {
-            if let Ok(json_string) = std::fs::read_to_string(path) {
-                match serde_json::from_str::<G2CrashMetrics>(&json_string) {
-                    Ok(crash_metrics) => { self.metrics = crash_metrics }
-                    Err(err) => return Err(anyhow!("{}", err)),
-                }
-            }
-        } else {
-            match G2CrashMetrics::new(path) {
-                Ok(crash_metrics) => { self.metrics = crash_metrics }
-                Err(err) => return Err(anyhow!("{}", err)),
-            }
-        }
+        let metrics = load_metrics(path)?;
+        self.set_metrics(metrics, crash_file_time(Path::new(path)));
         Ok(())
     }
 
-    fn save_metrics(&mut self) {
+    fn set_metrics(&mut self, metrics: G2CrashMetrics, crash_time: Option<SystemTime>) {
+        let previous = std::mem::replace(&mut self.metrics, metrics);
+        self.data.crash_time = crash_time;
+        let export = CrashExport::new(&self.metrics, &self.data.scenes, crash_time);
+        self.data.diagnoses = self.data.rules.diagnose(&export).into_iter().cloned().collect();
+        //reloading the same settings, e.g. when live update sees the file touched, keeps the comparison
+        if previous.settings_info.string.0 == self.metrics.settings_info.string.0 {
+            return;
+        }
+        let previous_settings = GraphicsSettings::parse(&previous.settings_info.to_string());
+        if !previous_settings.is_empty() {
+            self.data.previous_settings = Some(previous_settings);
+        }
+    }
+
+    //renders a picked template, else the user template next to the executable or the built-in markdown one
+    fn save_report(&mut self, pick_template: bool) -> Result<Option<PathBuf>, Error> {
+        let template = if pick_template {
+            let template = FileDialog::new()
+                .set_location("~/Documents")
+                .add_filter("report template", &["jinja", "j2", "md", "txt"])
+                .show_open_single_file()?;
+            match template {
+                Some(template) => Some(template),
+                None => return Ok(None),
+            }
+        } else {
+            None
+        };
+
         let path = FileDialog::new()
             .set_location("~/Documents")
-            .add_filter("crash_metrics.json", &["json"])
-            .show_save_single_file()
-            .unwrap();
+            .add_filter("bug report", &["md", "txt"])
+            .show_save_single_file()?;
 
         let path = match path {
             Some(path) => path,
-            None => return,
+            None => return Ok(None),
         };
 
-        // Convert the metrics to a JSON string.
-        let serialized = serde_json::to_string(&self.metrics).unwrap();
+        let export = CrashExport::new(&self.metrics, &self.data.scenes, self.data.crash_time);
+        let report = bug_report::render(&bug_report::load_template(template.as_deref())?, &export)?;
+        std::fs::write(&path, report)?;
+        Ok(Some(path))
+    }
+
+    //returns the path that was written, none when the dialog was cancelled
+    fn save_metrics(&mut self) -> Result<Option<PathBuf>, Error> {
+        let selected = self.data.export_format;
+        let path = FileDialog::new()
+            .set_location("~/Documents")
+            .add_filter(&format!("crash_metrics ({})", selected.name()), &[selected.extension()])
+            .show_save_single_file()?;
+
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(None),
+        };
 
-        std::fs::write(path, serialized.as_bytes()).expect("Couldnt write to file");
+        //typing another known extension overrides the selected format
+        let format = match ExportFormat::from_path(&path) {
+            Some(format) if format.extension() != selected.extension() => format,
+            _ => selected,
+        };
+        let export = CrashExport::new(&self.metrics, &self.data.scenes, self.data.crash_time);
+        std::fs::write(&path, format.write(&[export])?)?;
+        Ok(Some(path))
     }
 }
\ No newline at end of file
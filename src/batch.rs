@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use anyhow::{anyhow, Error};
+use crate::crash_export::{load_metrics, CrashExport};
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::modules::Module;
+use crate::scenes::SceneTable;
+use crate::timing::crash_file_time;
+
+const CRASH_EXTENSIONS: [&str; 2] = ["dat", "json"];
+
+pub struct BatchCrash {
+    pub path: PathBuf,
+    pub metrics: G2CrashMetrics,
+    pub crash_time: Option<SystemTime>,
+}
+
+impl BatchCrash {
+    pub fn export<'a>(&'a self, scenes: &SceneTable) -> CrashExport<'a> {
+        CrashExport::new(&self.metrics, scenes, self.crash_time)
+    }
+}
+
+//every crash file below a directory, plus the files that looked like crashes but failed to load
+pub struct Batch {
+    pub crashes: Vec<BatchCrash>,
+    pub failures: Vec<(PathBuf, Error)>,
+}
+
+impl Batch {
+    pub fn load_dir(dir: &Path) -> Result<Batch, Error> {
+        if !dir.is_dir() {
+            return Err(anyhow!("{} is not a directory", dir.display()));
+        }
+        let mut batch = Batch { crashes: vec![], failures: vec![] };
+        for path in crash_files(dir)? {
+            match load_metrics(&path.to_string_lossy()) {
+                Ok(metrics) => batch.crashes.push(BatchCrash {
+                    crash_time: crash_file_time(&path),
+                    path,
+                    metrics,
+                }),
+                Err(e) => batch.failures.push((path, e)),
+            }
+        }
+        Ok(batch)
+    }
+}
+
+//crash files below a directory in a stable order
+pub fn crash_files(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut paths = vec![];
+    collect_crash_files(dir, &mut paths)?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn collect_crash_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_crash_files(&path, paths)?;
+        } else if path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| CRASH_EXTENSIONS.contains(&extension)) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+//the game executable's pdb identifies the build the crash came from
+pub fn build_id(modules: &[Module]) -> Option<String> {
+    modules.iter()
+        .find(|module| module.name.to_lowercase().ends_with(".exe"))
+        .map(|module| format!("{} {}/{}", module.name, module.pdb_guid, module.pdb_age))
+}
+
+//how often each value occurs, in no particular order
+pub fn count(values: impl IntoIterator<Item = String>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts.into_iter().collect()
+}
+
+//ranks values by how often they occur, most common first
+pub fn count_by<T>(items: &[T], key: impl Fn(&T) -> String) -> Vec<(String, usize)> {
+    let mut counts = count(items.iter().map(key));
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
@@ -0,0 +1,58 @@
+use std::path::Path;
+use anyhow::{anyhow, Error};
+use minijinja::Environment;
+use crate::crash_export::CrashExport;
+
+static BUILTIN_TEMPLATE: &str = include_str!("../assets/bug_report.md");
+
+//template next to the executable that replaces the built-in markdown one
+pub const USER_TEMPLATE_FILE: &str = "bug_report.jinja";
+
+//an explicit template wins over the user template, which wins over the built-in one
+pub fn load_template(path: Option<&Path>) -> Result<String, Error> {
+    if let Some(path) = path {
+        return std::fs::read_to_string(path).map_err(|e| anyhow!("failed to read template {}: {e}", path.display()));
+    }
+    let user_template = std::env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(USER_TEMPLATE_FILE)))
+        .filter(|path| path.exists());
+    match user_template {
+        Some(path) => std::fs::read_to_string(&path).map_err(|e| anyhow!("failed to read template {}: {e}", path.display())),
+        None => Ok(BUILTIN_TEMPLATE.to_string()),
+    }
+}
+
+//templates see the same document as the json export: schema_version, raw and derived
+pub fn render(template: &str, export: &CrashExport) -> Result<String, Error> {
+    let mut env = Environment::new();
+    env.add_filter("hex", |value: u64| format!("0x{value:x}"));
+    env.add_template("report", template)?;
+    Ok(env.get_template("report")?.render(export)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::g2_crash_metrics::{G2CrashMetrics, NullStringS};
+    use crate::scenes::SceneTable;
+
+    #[test]
+    fn builtin_template_shows_display_names() {
+        let metrics = G2CrashMetrics {
+            uptimems: 5000,
+            online_server_version: NullStringS::from_str("peacock-v6.5").unwrap(),
+            ..Default::default()
+        };
+        let report = render(BUILTIN_TEMPLATE, &CrashExport::new(&metrics, &SceneTable::bundled(), None)).unwrap();
+        assert!(report.contains("| Uptime | 5s (startup crash) |"), "{report}");
+        assert!(report.contains("| Server | peacock-v6.5 (Peacock) |"), "{report}");
+        assert!(report.contains("| Net role | Offline |"), "{report}");
+    }
+
+    #[test]
+    fn missing_server_version_reads_none() {
+        let metrics = G2CrashMetrics::default();
+        let report = render(BUILTIN_TEMPLATE, &CrashExport::new(&metrics, &SceneTable::bundled(), None)).unwrap();
+        assert!(report.contains("| Server | none (none) |"), "{report}");
+    }
+}
@@ -0,0 +1,56 @@
+use md5::{Digest, Md5};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::modules::{find_module, Module};
+
+//frames that count towards the crash signature
+const SIGNATURE_FRAMES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Frame {
+    pub address: u64,
+    pub module: Option<String>,
+    pub offset: Option<u64>,
+}
+
+impl Frame {
+    //module relative locations stay the same across runs, absolute addresses don't because of aslr
+    pub fn location(&self) -> String {
+        match (&self.module, self.offset) {
+            (Some(module), Some(offset)) => format!("{module}+0x{offset:x}"),
+            _ => format!("0x{:x}", self.address),
+        }
+    }
+}
+
+//the callstack field is a ';' separated list of hex return addresses, innermost first
+pub fn parse_callstack(callstack: &str, modules: &[Module]) -> Vec<Frame> {
+    callstack.split(';')
+        .filter_map(|address| u64::from_str_radix(address.trim(), 16).ok())
+        .map(|address| match find_module(modules, address) {
+            Some((module, offset)) => Frame { address, module: Some(module.name.clone()), offset: Some(offset) },
+            None => Frame { address, module: None, offset: None },
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Signature {
+    //exception code followed by the innermost module relative frames
+    pub text: String,
+    //short stable hash of the text, handy for grouping and searching
+    pub id: String,
+}
+
+impl Signature {
+    pub fn new(exception_code: u32, frames: &[Frame]) -> Signature {
+        let resolved: Vec<&Frame> = frames.iter().filter(|frame| frame.module.is_some()).collect();
+        let frames: Vec<&Frame> = if resolved.is_empty() { frames.iter().collect() } else { resolved };
+        let locations: Vec<String> = frames.iter().take(SIGNATURE_FRAMES).map(|frame| frame.location()).collect();
+
+        let text = format!("0x{exception_code:08X} {}", locations.join(" < "));
+        let digest = Md5::digest(text.to_lowercase().as_bytes());
+        let id = digest[..6].iter().map(|byte| format!("{byte:02x}")).collect();
+        Signature { text, id }
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Error};
+use cgmath::{Deg, Euler, Matrix3, Quaternion, Rad};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraFormat {
+    SMatrix43,
+    QuickEntity,
+    Teleport,
+}
+
+impl CameraFormat {
+    pub fn from_name(name: &str) -> Option<CameraFormat> {
+        match name {
+            "smatrix43" | "smatrix" => Some(CameraFormat::SMatrix43),
+            "quickentity" | "qn" => Some(CameraFormat::QuickEntity),
+            "teleport" => Some(CameraFormat::Teleport),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CameraFormat::SMatrix43 => "SMatrix43",
+            CameraFormat::QuickEntity => "QuickEntity",
+            CameraFormat::Teleport => "teleport",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Vector3<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+impl<T: Copy> Vector3<T> {
+    fn new(values: [T; 3]) -> Vector3<T> {
+        Vector3 { x: values[0], y: values[1], z: values[2] }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SMatrix43 {
+    x_axis: Vector3<f32>,
+    y_axis: Vector3<f32>,
+    z_axis: Vector3<f32>,
+    trans: Vector3<f32>,
+}
+
+#[derive(Serialize)]
+struct QuickEntityTransform {
+    rotation: Vector3<f64>,
+    position: Vector3<f32>,
+}
+
+//the camera string holds the x, y and z axis rows of the rotation followed by the translation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraTransform {
+    pub rotation: [[f32; 3]; 3],
+    pub position: [f32; 3],
+}
+
+impl CameraTransform {
+    pub fn parse(camera: &str) -> Result<CameraTransform, Error> {
+        let values = camera.split(',')
+            .map(|num| num.trim().parse::<f32>().map_err(|_| anyhow!("'{}' is not a number", num.trim())))
+            .collect::<Result<Vec<f32>, Error>>()?;
+
+        if values.len() != 12 {
+            return Err(anyhow!("expected 12 values, found {}", values.len()));
+        }
+
+        Ok(CameraTransform {
+            rotation: [
+                [values[0], values[1], values[2]],
+                [values[3], values[4], values[5]],
+                [values[6], values[7], values[8]],
+            ],
+            position: [values[9], values[10], values[11]],
+        })
+    }
+
+    pub fn euler_degrees(&self) -> [f32; 3] {
+        let r = self.rotation;
+        let rot_mat: Matrix3<f32> = Matrix3::new(
+            r[0][0], r[0][1], r[0][2],
+            r[1][0], r[1][1], r[1][2],
+            r[2][0], r[2][1], r[2][2]);
+
+        let quaternion: Quaternion<f32> = Quaternion::from(rot_mat);
+
+        let euler: Euler<Rad<f32>> = Euler::from(quaternion);
+        [Deg::from(-euler.x).0, Deg::from(-euler.y).0, Deg::from(-euler.z).0]
+    }
+
+    //cameras look down their negative z axis, glacier is z-up so x and y are the ground plane
+    pub fn heading(&self) -> (f64, f64) {
+        let forward = self.rotation[2];
+        let (x, y) = (-forward[0] as f64, -forward[1] as f64);
+        let length = (x * x + y * y).sqrt();
+        if length < f64::EPSILON {
+            (0.0, 0.0)
+        } else {
+            (x / length, y / length)
+        }
+    }
+
+    //xyz euler angles in degrees, as used by QuickEntity transforms
+    pub fn quickentity_rotation(&self) -> [f64; 3] {
+        let [x_axis, y_axis, z_axis] = self.rotation.map(|row| row.map(|v| v as f64));
+        let y = x_axis[2].clamp(-1.0, 1.0).asin();
+        let (x, z) = if x_axis[2].abs() < 0.9999999 {
+            ((-y_axis[2]).atan2(z_axis[2]), (-x_axis[1]).atan2(x_axis[0]))
+        } else {
+            (y_axis[1].atan2(y_axis[0]), 0.0)
+        };
+        //adding zero turns -0.0 into 0.0
+        [x.to_degrees() + 0.0, y.to_degrees() + 0.0, z.to_degrees() + 0.0]
+    }
+
+    pub fn export(&self, format: CameraFormat) -> String {
+        match format {
+            CameraFormat::SMatrix43 => {
+                let matrix = SMatrix43 {
+                    x_axis: Vector3::new(self.rotation[0]),
+                    y_axis: Vector3::new(self.rotation[1]),
+                    z_axis: Vector3::new(self.rotation[2]),
+                    trans: Vector3::new(self.position),
+                };
+                serde_json::to_string_pretty(&matrix).unwrap_or_default()
+            }
+            CameraFormat::QuickEntity => {
+                let transform = QuickEntityTransform {
+                    rotation: Vector3::new(self.quickentity_rotation()),
+                    position: Vector3::new(self.position),
+                };
+                serde_json::to_string_pretty(&transform).unwrap_or_default()
+            }
+            CameraFormat::Teleport => {
+                let [px, py, pz] = self.position;
+                let [rx, ry, rz] = self.quickentity_rotation();
+                format!("teleport {px:.3} {py:.3} {pz:.3} {rx:.2} {ry:.2} {rz:.2}")
+            }
+        }
+    }
+}
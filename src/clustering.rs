@@ -0,0 +1,180 @@
+use crate::callstack::Frame;
+
+pub const DEFAULT_THRESHOLD: f64 = 0.7;
+//frames below this depth are rarely related to the crash itself
+const MAX_FRAMES: usize = 16;
+//offsets this close in the same module are most likely the same function, shifted by inlining
+const NEAR_OFFSET: u64 = 0x100;
+
+//module relative frames only, absolute addresses change with aslr
+pub fn normalize(frames: &[Frame]) -> Vec<Frame> {
+    let resolved: Vec<Frame> = frames.iter().filter(|frame| frame.module.is_some()).cloned().collect();
+    let frames = if resolved.is_empty() { frames.to_vec() } else { resolved };
+    frames.into_iter().take(MAX_FRAMES).collect()
+}
+
+//the top frame matters most, each deeper frame a bit less
+fn weight(depth: usize) -> f64 {
+    1.0 / (1.0 + depth as f64 * 0.5)
+}
+
+fn substitution_cost(a: &Frame, b: &Frame) -> f64 {
+    match (&a.module, a.offset, &b.module, b.offset) {
+        _ if a.location() == b.location() => 0.0,
+        (Some(module_a), Some(offset_a), Some(module_b), Some(offset_b)) if module_a == module_b && offset_a.abs_diff(offset_b) <= NEAR_OFFSET => 0.25,
+        _ => 1.0,
+    }
+}
+
+//1 - the weighted edit distance between two normalized stacks, 1 is identical
+pub fn similarity(a: &[Frame], b: &[Frame]) -> f64 {
+    let total_a: f64 = (0..a.len()).map(weight).sum();
+    let total_b: f64 = (0..b.len()).map(weight).sum();
+    let total = total_a.max(total_b);
+    if total == 0.0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<f64> = (0..=b.len()).map(|j| (0..j).map(weight).sum()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![previous[0] + weight(i - 1)];
+        for j in 1..=b.len() {
+            let pair_weight = (weight(i - 1) + weight(j - 1)) / 2.0;
+            let cost = (previous[j] + weight(i - 1))
+                .min(row[j - 1] + weight(j - 1))
+                .min(previous[j - 1] + pair_weight * substitution_cost(&a[i - 1], &b[j - 1]));
+            row.push(cost);
+        }
+        previous = row;
+    }
+    (1.0 - previous[b.len()] / total).max(0.0)
+}
+
+#[derive(Debug, Clone)]
+pub struct Cluster {
+    //indices into the clustered stacks
+    pub members: Vec<usize>,
+    //the member most similar to all the others
+    pub representative: usize,
+}
+
+//labels a cluster by its rank and the signature of its representative
+pub fn cluster_label(rank: usize, signature: &str) -> String {
+    format!("#{rank} {signature}")
+}
+
+//for the threshold options, nan would put every crash in a cluster of its own
+pub fn parse_threshold(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(threshold) if (0.0..=1.0).contains(&threshold) => Ok(threshold),
+        _ => Err(format!("the threshold must be a number from 0 to 1, not '{value}'")),
+    }
+}
+
+//every stack joins the most similar cluster leader at or above the threshold, or leads a new cluster
+pub fn cluster(stacks: &[Vec<Frame>], threshold: f64) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = vec![];
+    for (i, stack) in stacks.iter().enumerate() {
+        let best = clusters.iter_mut()
+            .map(|cluster| {
+                let leader = cluster.members[0];
+                (similarity(&stacks[leader], stack), cluster)
+            })
+            .filter(|(similarity, _)| *similarity >= threshold)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        match best {
+            Some((_, cluster)) => cluster.members.push(i),
+            None => clusters.push(Cluster { members: vec![i], representative: i }),
+        }
+    }
+
+    for cluster in clusters.iter_mut() {
+        let scores: Vec<f64> = cluster.members.iter()
+            .map(|member| cluster.members.iter().map(|other| similarity(&stacks[*member], &stacks[*other])).sum())
+            .collect();
+        //the first member wins ties
+        let mut best = 0;
+        for (i, score) in scores.iter().enumerate() {
+            if *score > scores[best] {
+                best = i;
+            }
+        }
+        cluster.representative = cluster.members[best];
+    }
+    clusters.sort_by(|a, b| b.members.len().cmp(&a.members.len()).then(a.members[0].cmp(&b.members[0])));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(module: &str, offset: u64) -> Frame {
+        Frame { address: 0x140000000 + offset, module: Some(module.to_string()), offset: Some(offset) }
+    }
+
+    fn stack(offsets: &[u64]) -> Vec<Frame> {
+        offsets.iter().map(|offset| frame("HITMAN3.exe", *offset)).collect()
+    }
+
+    #[test]
+    fn identical_and_disjoint_stacks() {
+        let a = stack(&[0x1000, 0x2000, 0x3000]);
+        assert_eq!(similarity(&a, &a), 1.0);
+        assert_eq!(similarity(&[], &[]), 1.0);
+        assert_eq!(similarity(&a, &stack(&[0x7000, 0x8000, 0x9000])), 0.0);
+    }
+
+    #[test]
+    fn top_frames_weigh_more() {
+        let a = stack(&[0x1000, 0x2000, 0x3000, 0x4000]);
+        let top_differs = similarity(&a, &stack(&[0x9000, 0x2000, 0x3000, 0x4000]));
+        let bottom_differs = similarity(&a, &stack(&[0x1000, 0x2000, 0x3000, 0x9000]));
+        assert!(top_differs < bottom_differs, "{top_differs} {bottom_differs}");
+    }
+
+    #[test]
+    fn nearby_offsets_are_nearly_the_same_frame() {
+        let a = stack(&[0x1000, 0x2000]);
+        let near = similarity(&a, &stack(&[0x1010, 0x2000]));
+        let far = similarity(&a, &stack(&[0x5000, 0x2000]));
+        assert!(near > far && near < 1.0, "{near} {far}");
+        let other_module = similarity(&a, &[frame("ntdll.dll", 0x1010), frame("HITMAN3.exe", 0x2000)]);
+        assert_eq!(other_module, far);
+    }
+
+    #[test]
+    fn normalize_keeps_resolved_frames() {
+        let mut frames = vec![Frame { address: 0x1234, module: None, offset: None }];
+        frames.extend(stack(&[0x1000; 20]));
+        let normalized = normalize(&frames);
+        assert_eq!(normalized.len(), MAX_FRAMES);
+        assert!(normalized.iter().all(|frame| frame.module.is_some()));
+        //stacks without any module keep their absolute addresses
+        assert_eq!(normalize(&frames[..1]).len(), 1);
+    }
+
+    #[test]
+    fn similar_stacks_share_a_cluster() {
+        let stacks = vec![
+            stack(&[0x1000, 0x2000, 0x3000]),
+            stack(&[0x7000, 0x8000, 0x9000]),
+            stack(&[0x1000, 0x2000, 0x3100]),
+            stack(&[0x1000, 0x2000, 0x3000]),
+        ];
+        let clusters = cluster(&stacks, DEFAULT_THRESHOLD);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, vec![0, 2, 3]);
+        assert_eq!(clusters[0].representative, 0);
+        assert_eq!(clusters[1].members, vec![1]);
+        //at 1 only identical stacks are clustered together
+        assert_eq!(cluster(&stacks, 1.0).len(), 3);
+    }
+
+    #[test]
+    fn thresholds_are_between_0_and_1() {
+        assert_eq!(parse_threshold("0.5"), Ok(0.5));
+        assert!(parse_threshold("1.5").is_err());
+        assert!(parse_threshold("NaN").is_err());
+    }
+}
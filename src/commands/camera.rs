@@ -0,0 +1,25 @@
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::camera::{CameraFormat, CameraTransform};
+use crate::crash_export::load_metrics;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "camera", description = "export the camera transform of a crash")]
+pub struct CameraCommand {
+    #[argh(positional, description = "path to the crash_metrics.dat or exported json file")]
+    pub metrics_path: String,
+    #[argh(option, default = "String::from(\"quickentity\")", description = "output format: smatrix43, quickentity or teleport (default: quickentity)")]
+    pub format: String,
+}
+
+impl CameraCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let format = CameraFormat::from_name(&self.format)
+            .ok_or_else(|| anyhow!("unknown camera format '{}', expected smatrix43, quickentity or teleport", self.format))?;
+        let metrics = load_metrics(&self.metrics_path)?;
+        let camera = CameraTransform::parse(&metrics.camera.to_string())
+            .map_err(|e| anyhow!("invalid camera data: {e}"))?;
+        println!("{}", camera.export(format));
+        Ok(())
+    }
+}
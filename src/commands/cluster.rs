@@ -0,0 +1,85 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::Error;
+use serde::Serialize;
+use crate::clustering::{parse_threshold, similarity, DEFAULT_THRESHOLD};
+use crate::corpus::{cluster_entries, scan, CorpusEntry};
+use crate::scenes::SceneTable;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "cluster", description = "group the crashes of a folder or crash database by callstack similarity")]
+pub struct ClusterCommand {
+    #[argh(positional, description = "folder searched recursively, or a crash database created by ingest")]
+    pub source: String,
+    #[argh(option, default = "DEFAULT_THRESHOLD", from_str_fn(parse_threshold), description = "similarity from 0 to 1 two callstacks need to share a cluster (default: 0.7)")]
+    pub threshold: f64,
+    #[argh(option, description = "file to write the json to (default: stdout)")]
+    pub output: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ClusterReport {
+    threshold: f64,
+    crashes: usize,
+    clusters: Vec<ClusterSummary>,
+}
+
+#[derive(Serialize)]
+struct ClusterSummary {
+    size: usize,
+    //distinct exact signatures merged into the cluster
+    signatures: Vec<String>,
+    representative: Member,
+    stack: Vec<String>,
+    members: Vec<Member>,
+}
+
+#[derive(Serialize)]
+struct Member {
+    crash: String,
+    signature: String,
+    //to the representative
+    similarity: f64,
+}
+
+impl ClusterCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let (mut entries, _) = scan(Path::new(&self.source), &SceneTable::load_default())?;
+        let clusters = cluster_entries(&mut entries, self.threshold);
+
+        let member = |entry: &CorpusEntry, representative: &CorpusEntry| Member {
+            crash: entry.origin.to_string(),
+            signature: entry.signature.clone(),
+            similarity: (similarity(&entry.stack, &representative.stack) * 1000.0).round() / 1000.0,
+        };
+        let report = ClusterReport {
+            threshold: self.threshold,
+            crashes: entries.len(),
+            clusters: clusters.iter()
+                .map(|cluster| {
+                    let representative = &entries[cluster.representative];
+                    let mut signatures: Vec<String> = vec![];
+                    for index in &cluster.members {
+                        if !signatures.contains(&entries[*index].signature) {
+                            signatures.push(entries[*index].signature.clone());
+                        }
+                    }
+                    ClusterSummary {
+                        size: cluster.members.len(),
+                        signatures,
+                        representative: member(representative, representative),
+                        stack: representative.stack.iter().map(|frame| frame.location()).collect(),
+                        members: cluster.members.iter().map(|index| member(&entries[*index], representative)).collect(),
+                    }
+                })
+                .collect(),
+        };
+
+        let json = serde_json::to_string_pretty(&report)?;
+        match &self.output {
+            Some(output) => std::fs::write(output, json)?,
+            None => println!("{json}"),
+        }
+        Ok(())
+    }
+}
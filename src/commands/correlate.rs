@@ -0,0 +1,96 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::Error;
+use serde::Serialize;
+use crate::clustering::{parse_threshold, DEFAULT_THRESHOLD};
+use crate::commands::query::print_table;
+use crate::corpus::{cluster_entries, scan};
+use crate::correlation::{correlate, SIGNIFICANCE};
+use crate::crash_db::QueryResult;
+use crate::scenes::SceneTable;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "correlate", description = "rank the settings and hardware over-represented in each crash cluster of a folder or crash database")]
+pub struct CorrelateCommand {
+    #[argh(positional, description = "folder searched recursively, or a crash database created by ingest")]
+    pub source: String,
+    #[argh(option, default = "DEFAULT_THRESHOLD", from_str_fn(parse_threshold), description = "similarity from 0 to 1 two callstacks need to share a cluster (default: 0.7)")]
+    pub threshold: f64,
+    #[argh(option, default = "2", description = "crashes of a cluster that need to share a value before it is ranked (default: 2)")]
+    pub min_count: usize,
+    #[argh(option, default = "30", description = "number of factors to list, 0 lists all (default: 30)")]
+    pub limit: usize,
+    #[argh(switch, description = "print json instead of a table")]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct Factor {
+    cluster: String,
+    cluster_size: usize,
+    factor: String,
+    value: String,
+    crashes: usize,
+    corpus_crashes: usize,
+    lift: f64,
+    chi_square: f64,
+    p_value: f64,
+    adjusted_p: f64,
+    significant: bool,
+}
+
+impl CorrelateCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let (mut entries, _) = scan(Path::new(&self.source), &SceneTable::load_default())?;
+        cluster_entries(&mut entries, self.threshold);
+        let mut correlations = correlate(&entries, self.min_count.max(1));
+        if self.limit > 0 {
+            correlations.truncate(self.limit);
+        }
+
+        let round = |value: f64| (value * 1000.0).round() / 1000.0;
+        let factors: Vec<Factor> = correlations.into_iter()
+            .map(|correlation| Factor {
+                significant: correlation.adjusted_p < SIGNIFICANCE,
+                cluster: correlation.cluster,
+                cluster_size: correlation.cluster_size,
+                factor: correlation.factor,
+                value: correlation.value,
+                crashes: correlation.count,
+                corpus_crashes: correlation.corpus_count,
+                lift: round(correlation.lift),
+                chi_square: round(correlation.chi_square),
+                p_value: round_p(correlation.p_value),
+                adjusted_p: round_p(correlation.adjusted_p),
+            })
+            .collect();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&factors)?);
+            return Ok(());
+        }
+        let total = entries.len();
+        let result = QueryResult {
+            columns: ["cluster", "factor", "in cluster", "in corpus", "lift", "chi²", "adjusted p"].iter().map(|column| column.to_string()).collect(),
+            rows: factors.iter()
+                .map(|factor| vec![
+                    factor.cluster.clone(),
+                    format!("{} = {}", factor.factor, factor.value),
+                    format!("{}/{}", factor.crashes, factor.cluster_size),
+                    format!("{}/{total}", factor.corpus_crashes),
+                    format!("{:.2}", factor.lift),
+                    format!("{:.2}", factor.chi_square),
+                    format!("{:.4}{}", factor.adjusted_p, if factor.significant { " *" } else { "" }),
+                ])
+                .collect(),
+        };
+        print_table(&result);
+        println!("* significant at a false discovery rate of {SIGNIFICANCE} (fisher's exact test, benjamini-hochberg adjusted)");
+        Ok(())
+    }
+}
+
+//p values are small, keep six decimals instead of three
+fn round_p(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::Error;
+use crate::batch::Batch;
+use crate::dashboard::write_site;
+use crate::rules::RuleSet;
+use crate::scenes::SceneTable;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "dashboard", description = "generate a static html dashboard from a directory of crashes")]
+pub struct DashboardCommand {
+    #[argh(positional, description = "directory with crash_metrics.dat or exported json files, searched recursively")]
+    pub crash_dir: String,
+    #[argh(option, default = "String::from(\"dashboard\")", description = "directory to write the site to (default: dashboard)")]
+    pub output: String,
+}
+
+impl DashboardCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let batch = Batch::load_dir(Path::new(&self.crash_dir))?;
+        for (path, e) in &batch.failures {
+            eprintln!("skipping {}: {e}", path.display());
+        }
+        let output = Path::new(&self.output);
+        let pages = write_site(&batch, &SceneTable::load_default(), &RuleSet::load_default(), output)?;
+        println!("wrote {} with {pages} crash pages", output.join("index.html").display());
+        Ok(())
+    }
+}
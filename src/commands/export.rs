@@ -0,0 +1,61 @@
+use std::io::Write;
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::callstack::Frame;
+use crate::clustering::{cluster, cluster_label, normalize, parse_threshold, DEFAULT_THRESHOLD};
+use crate::crash_export::{load_metrics, CrashExport};
+use crate::exporters::ExportFormat;
+use crate::scenes::SceneTable;
+use crate::timing::crash_file_time;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "export", description = "convert crashes to json, yaml, toml, cbor, msgpack, csv or an html report")]
+pub struct ExportCommand {
+    #[argh(positional, description = "paths to crash_metrics.dat or exported json files")]
+    pub metrics_paths: Vec<String>,
+    #[argh(option, description = "output format: json, pretty, yaml, toml, cbor, msgpack, csv or html (default: from the output extension, else json)")]
+    pub format: Option<String>,
+    #[argh(option, description = "file to write the export to (default: stdout)")]
+    pub output: Option<String>,
+    #[argh(option, default = "DEFAULT_THRESHOLD", from_str_fn(parse_threshold), description = "callstack similarity from 0 to 1 crashes exported together need to share a cluster (default: 0.7)")]
+    pub threshold: f64,
+}
+
+impl ExportCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let format = match (&self.format, &self.output) {
+            (Some(name), _) => ExportFormat::from_name(name)
+                .ok_or_else(|| anyhow!("unknown export format '{name}', expected json, pretty, yaml, toml, cbor, msgpack, csv or html"))?,
+            (None, Some(output)) => ExportFormat::from_path(Path::new(output)).unwrap_or(ExportFormat::Json),
+            (None, None) => ExportFormat::Json,
+        };
+
+        let scenes = SceneTable::load_default();
+        let crashes = self.metrics_paths.iter()
+            .map(|path| {
+                let metrics = load_metrics(path).map_err(|e| anyhow!("failed to load {path}: {e}"))?;
+                Ok((metrics, crash_file_time(Path::new(path))))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut exports: Vec<CrashExport> = crashes.iter()
+            .map(|(metrics, crash_time)| CrashExport::new(metrics, &scenes, *crash_time))
+            .collect();
+        if exports.len() > 1 {
+            let stacks: Vec<Vec<Frame>> = exports.iter().map(|export| normalize(&export.derived.frames)).collect();
+            for (i, found) in cluster(&stacks, self.threshold).iter().enumerate() {
+                let label = cluster_label(i + 1, &exports[found.representative].derived.signature.text);
+                for member in &found.members {
+                    exports[*member].cluster = Some(label.clone());
+                }
+            }
+        }
+
+        let bytes = format.write(&exports)?;
+        match &self.output {
+            Some(output) => std::fs::write(output, bytes).map_err(|e| anyhow!("failed to write {output}: {e}"))?,
+            None => std::io::stdout().write_all(&bytes)?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::corpus::scan;
+use crate::flamegraph::{fold, folded_text, render_svg, stack_names, FlamegraphFormat};
+use crate::scenes::SceneTable;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "flamegraph", description = "aggregate the callstacks of a folder or crash database into folded stacks or an svg flamegraph")]
+pub struct FlamegraphCommand {
+    #[argh(positional, description = "folder searched recursively, or a crash database created by ingest")]
+    pub source: String,
+    #[argh(option, description = "folded or svg (default: the output extension, else folded)")]
+    pub format: Option<String>,
+    #[argh(switch, description = "use module names instead of module+offset frames")]
+    pub modules: bool,
+    #[argh(option, description = "file to write to (default: stdout)")]
+    pub output: Option<String>,
+}
+
+impl FlamegraphCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let format = match (&self.format, &self.output) {
+            (Some(name), _) => FlamegraphFormat::from_name(name).ok_or_else(|| anyhow!("unknown format '{name}', expected folded or svg"))?,
+            (None, Some(output)) => Path::new(output).extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(FlamegraphFormat::from_name)
+                .unwrap_or(FlamegraphFormat::Folded),
+            (None, None) => FlamegraphFormat::Folded,
+        };
+
+        let (entries, _) = scan(Path::new(&self.source), &SceneTable::load_default())?;
+        let stacks: Vec<Vec<String>> = entries.iter().map(|entry| stack_names(&entry.frames, self.modules)).collect();
+        let folded = fold(&stacks);
+        let text = match format {
+            FlamegraphFormat::Folded => folded_text(&folded),
+            FlamegraphFormat::Svg => render_svg(&folded, &format!("Crash flamegraph: {} crashes in {}", entries.len(), self.source)),
+        };
+        match &self.output {
+            Some(output) => std::fs::write(output, text)?,
+            None => print!("{text}"),
+        }
+        Ok(())
+    }
+}
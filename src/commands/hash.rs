@@ -0,0 +1,36 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::runtime_id::{format_id, parse_id, runtime_id, HashList};
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "hash", description = "compute Glacier runtime resource ids, or look ids up in a hash list")]
+pub struct HashCommand {
+    #[argh(positional, description = "resource paths to hash, or 16 digit runtime ids to look up")]
+    pub values: Vec<String>,
+    #[argh(option, description = "hash list used for the reverse lookup (default: hash_list.txt next to the executable)")]
+    pub hash_list: Option<String>,
+}
+
+impl HashCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let hash_list = match &self.hash_list {
+            Some(path) => Some(HashList::load(Path::new(path))?),
+            None => HashList::load_default(),
+        };
+
+        for value in &self.values {
+            if let Some(id) = parse_id(value) {
+                let hash_list = hash_list.as_ref().ok_or_else(|| anyhow!("a hash list is needed to look up {value}"))?;
+                println!("{} {}", format_id(id), hash_list.lookup(id).unwrap_or("not found"));
+            } else {
+                let id = runtime_id(value);
+                match hash_list.as_ref().and_then(|hash_list| hash_list.lookup(id)) {
+                    Some(known) => println!("{} {value} (hash list: {known})", format_id(id)),
+                    None => println!("{} {value}", format_id(id)),
+                }
+            }
+        }
+        Ok(())
+    }
+}
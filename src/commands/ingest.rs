@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::batch::crash_files;
+use crate::crash_db::{CrashDb, Ingested, DEFAULT_DB};
+use crate::scenes::SceneTable;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "ingest", description = "store crashes in a sqlite database, skipping files that were ingested before")]
+pub struct IngestCommand {
+    #[argh(positional, description = "crash_metrics.dat or exported files, or directories to search recursively")]
+    pub paths: Vec<String>,
+    #[argh(option, default = "String::from(DEFAULT_DB)", description = "database file (default: crashes.db)")]
+    pub db: String,
+}
+
+impl IngestCommand {
+    pub fn run(self) -> Result<(), Error> {
+        if self.paths.is_empty() {
+            return Err(anyhow!("no crash files given"));
+        }
+        let mut paths: Vec<PathBuf> = vec![];
+        for path in &self.paths {
+            let path = Path::new(path);
+            if path.is_dir() {
+                paths.extend(crash_files(path)?);
+            } else {
+                paths.push(path.to_path_buf());
+            }
+        }
+
+        let mut db = CrashDb::open_for_ingest(Path::new(&self.db))?;
+        let scenes = SceneTable::load_default();
+        let (mut new, mut duplicates, mut failed) = (0, 0, 0);
+        for path in &paths {
+            match db.ingest(path, &scenes) {
+                Ok(Ingested::New(id)) => {
+                    new += 1;
+                    println!("{}: crash {id}", path.display());
+                }
+                Ok(Ingested::Duplicate(id)) => {
+                    duplicates += 1;
+                    println!("{}: already ingested as crash {id}", path.display());
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("{}: {e}", path.display());
+                }
+            }
+        }
+        println!("{new} new, {duplicates} duplicates, {failed} failed");
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use argh::FromArgs;
+use anyhow::Error;
+
+pub mod camera;
+pub mod cluster;
+pub mod correlate;
+pub mod dashboard;
+pub mod export;
+pub mod flamegraph;
+pub mod hash;
+pub mod ingest;
+pub mod query;
+pub mod report;
+pub mod schema;
+pub mod settings;
+pub mod status;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Status(status::StatusCommand),
+    Settings(settings::SettingsCommand),
+    Hash(hash::HashCommand),
+    Camera(camera::CameraCommand),
+    Export(export::ExportCommand),
+    Schema(schema::SchemaCommand),
+    Report(report::ReportCommand),
+    Dashboard(dashboard::DashboardCommand),
+    Ingest(ingest::IngestCommand),
+    Query(query::QueryCommand),
+    Cluster(cluster::ClusterCommand),
+    Flamegraph(flamegraph::FlamegraphCommand),
+    Correlate(correlate::CorrelateCommand),
+}
+
+impl Command {
+    pub fn run(self) -> Result<(), Error> {
+        match self {
+            Command::Status(command) => command.run(),
+            Command::Settings(command) => command.run(),
+            Command::Hash(command) => command.run(),
+            Command::Camera(command) => command.run(),
+            Command::Export(command) => command.run(),
+            Command::Schema(command) => command.run(),
+            Command::Report(command) => command.run(),
+            Command::Dashboard(command) => command.run(),
+            Command::Ingest(command) => command.run(),
+            Command::Query(command) => command.run(),
+            Command::Cluster(command) => command.run(),
+            Command::Flamegraph(command) => command.run(),
+            Command::Correlate(command) => command.run(),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use unicode_width::UnicodeWidthStr;
+use crate::crash_db::{CrashDb, QueryResult, DEFAULT_DB, REPORTS};
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "query", description = "run sql or a canned report against a crash database, run without arguments to list the reports")]
+pub struct QueryCommand {
+    #[argh(positional, description = "sql statement or report name")]
+    pub sql: Option<String>,
+    #[argh(option, default = "String::from(DEFAULT_DB)", description = "database file (default: crashes.db)")]
+    pub db: String,
+    #[argh(switch, description = "print the result as csv")]
+    pub csv: bool,
+}
+
+impl QueryCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let Some(sql) = &self.sql else {
+            for (name, description, _) in REPORTS {
+                println!("{name:<12} {description}");
+            }
+            return Ok(());
+        };
+        if !Path::new(&self.db).exists() {
+            return Err(anyhow!("{} does not exist, ingest some crashes first", self.db));
+        }
+        let result = CrashDb::open(Path::new(&self.db))?.query(sql)?;
+        if self.csv {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            writer.write_record(&result.columns)?;
+            for row in &result.rows {
+                writer.write_record(row)?;
+            }
+            writer.flush()?;
+        } else {
+            print_table(&result);
+        }
+        Ok(())
+    }
+}
+
+pub fn print_table(result: &QueryResult) {
+    let mut widths: Vec<usize> = result.columns.iter().map(|column| column.width()).collect();
+    for row in &result.rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.width());
+        }
+    }
+    let line = |values: &[String]| {
+        let cells: Vec<String> = values.iter().zip(&widths)
+            .map(|(value, width)| format!("{value}{}", " ".repeat(width - value.width())))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    };
+    line(&result.columns);
+    line(&widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>());
+    for row in &result.rows {
+        line(row);
+    }
+    println!("({} rows)", result.rows.len());
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::bug_report::{load_template, render};
+use crate::crash_export::{load_metrics, CrashExport};
+use crate::scenes::SceneTable;
+use crate::timing::crash_file_time;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "report", description = "write a bug report for a crash from a template")]
+pub struct ReportCommand {
+    #[argh(positional, description = "path to the crash_metrics.dat or exported json file")]
+    pub metrics_path: String,
+    #[argh(option, description = "template to render (default: bug_report.jinja next to the executable, else the built-in markdown template)")]
+    pub template: Option<String>,
+    #[argh(option, description = "file to write the report to (default: stdout)")]
+    pub output: Option<String>,
+}
+
+impl ReportCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let metrics = load_metrics(&self.metrics_path)?;
+        let crash_time = crash_file_time(Path::new(&self.metrics_path));
+        let export = CrashExport::new(&metrics, &SceneTable::load_default(), crash_time);
+        let template = load_template(self.template.as_deref().map(Path::new))?;
+        let report = render(&template, &export)?;
+        match &self.output {
+            Some(output) => std::fs::write(output, report).map_err(|e| anyhow!("failed to write {output}: {e}"))?,
+            None => print!("{report}"),
+        }
+        Ok(())
+    }
+}
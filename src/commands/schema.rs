@@ -0,0 +1,21 @@
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::crash_export::json_schema;
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "schema", description = "print the json schema of exported crashes")]
+pub struct SchemaCommand {
+    #[argh(option, description = "file to write the schema to (default: stdout)")]
+    pub output: Option<String>,
+}
+
+impl SchemaCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let schema = json_schema();
+        match &self.output {
+            Some(output) => std::fs::write(output, schema).map_err(|e| anyhow!("failed to write {output}: {e}"))?,
+            None => println!("{schema}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::crash_export::load_metrics;
+use crate::graphics_presets::GraphicsPreset;
+use crate::graphics_settings::GraphicsSettings;
+use crate::settings_profile::{read_profile, ProfileFormat};
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "settings", description = "export the graphics settings of a crash or compare them to a profile")]
+pub struct SettingsCommand {
+    #[argh(positional, description = "path to the crash_metrics.dat or exported json file")]
+    pub metrics_path: String,
+    #[argh(option, description = "export format: reg or profile")]
+    pub export: Option<String>,
+    #[argh(option, description = "file to write the export to (default: stdout)")]
+    pub output: Option<String>,
+    #[argh(option, description = "key=value profile to compare the crash settings against")]
+    pub compare: Option<String>,
+}
+
+impl SettingsCommand {
+    pub fn run(self) -> Result<(), Error> {
+        let metrics = load_metrics(&self.metrics_path)?;
+        let settings = GraphicsSettings::parse(&metrics.settings_info.to_string());
+
+        if let Some(profile_path) = &self.compare {
+            let profile = GraphicsPreset::new(profile_path.clone(), read_profile(Path::new(profile_path))?);
+            let deviations = profile.deviations(&settings);
+            if deviations.is_empty() {
+                println!("The crash settings match {profile_path}");
+            }
+            for deviation in deviations {
+                println!("{}", deviation.describe("the profile"));
+            }
+        }
+
+        if let Some(format) = &self.export {
+            let format = ProfileFormat::from_name(format).ok_or_else(|| anyhow!("unknown export format '{format}', expected reg or profile"))?;
+            let exported = format.write(&settings);
+            match &self.output {
+                Some(output) => std::fs::write(output, exported)?,
+                None => print!("{exported}"),
+            }
+        } else if self.compare.is_none() {
+            print!("{}", ProfileFormat::KeyValue.write(&settings));
+        }
+        Ok(())
+    }
+}
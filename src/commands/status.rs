@@ -0,0 +1,44 @@
+use argh::FromArgs;
+use anyhow::{anyhow, Error};
+use crate::status_codes::{search_name, parse_code, CodeKind, DecodedCode};
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "status", description = "decode an NTSTATUS, HRESULT or Win32 error code, or look one up by name")]
+pub struct StatusCommand {
+    #[argh(positional, description = "code (hex with 0x prefix or decimal) or (partial) symbolic name")]
+    pub query: String,
+    #[argh(option, description = "interpret the code as ntstatus, hresult or win32 instead of guessing")]
+    pub kind: Option<String>,
+}
+
+impl StatusCommand {
+    pub fn run(self) -> Result<(), Error> {
+        if let Some(code) = parse_code(&self.query) {
+            let decoded = match self.kind.as_deref() {
+                None => DecodedCode::guess(code),
+                Some("ntstatus") => DecodedCode::new(code, CodeKind::NtStatus),
+                Some("hresult") => DecodedCode::new(code, CodeKind::HResult),
+                Some("win32") => DecodedCode::new(code, CodeKind::Win32),
+                Some(kind) => return Err(anyhow!("unknown code kind '{kind}', expected ntstatus, hresult or win32")),
+            };
+            for line in decoded.lines() {
+                println!("{line}");
+            }
+            return Ok(());
+        }
+
+        let matches = search_name(&self.query);
+        if matches.is_empty() {
+            return Err(anyhow!("no code found matching '{}'", self.query));
+        }
+        for (i, known) in matches.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            for line in DecodedCode::new(known.code, known.kind).lines() {
+                println!("{line}");
+            }
+        }
+        Ok(())
+    }
+}
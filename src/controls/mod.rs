@@ -1,2 +1,3 @@
 pub mod stateful_list;
-pub mod stateful_tabs;
\ No newline at end of file
+pub mod stateful_tabs;
+pub mod stateful_tree;
\ No newline at end of file
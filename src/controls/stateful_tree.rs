@@ -0,0 +1,135 @@
+use tui::widgets::ListState;
+
+pub struct TreeNode {
+    pub label: String,
+    pub highlight: bool,
+    pub expanded: bool,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn leaf(label: String) -> TreeNode {
+        TreeNode { label, highlight: false, expanded: false, children: vec![] }
+    }
+
+    pub fn branch(label: String, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode { label, highlight: false, expanded: true, children }
+    }
+
+    pub fn highlighted(mut self) -> TreeNode {
+        self.highlight = true;
+        self
+    }
+}
+
+//a visible row of the tree, the path holds the child index at every depth
+pub struct TreeRow<'a> {
+    pub depth: usize,
+    pub path: Vec<usize>,
+    pub node: &'a TreeNode,
+}
+
+pub struct StatefulTree {
+    pub state: ListState,
+    pub items: Vec<TreeNode>,
+}
+
+impl StatefulTree {
+    pub fn with_items(items: Vec<TreeNode>) -> StatefulTree {
+        StatefulTree {
+            state: ListState::default(),
+            items,
+        }
+    }
+
+    pub fn rows(&self) -> Vec<TreeRow<'_>> {
+        let mut rows = vec![];
+        for (i, node) in self.items.iter().enumerate() {
+            push_rows(node, vec![i], &mut rows);
+        }
+        rows
+    }
+
+    pub fn next(&mut self) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.rows().len();
+        if len == 0 {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) if i > 0 && i < len => i - 1,
+            _ => len - 1,
+        };
+        self.state.select(Some(i));
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_expanded(None);
+    }
+
+    pub fn expand(&mut self) {
+        self.set_expanded(Some(true));
+    }
+
+    pub fn collapse(&mut self) {
+        self.set_expanded(Some(false));
+    }
+
+    //selects the first highlighted node, expanding its parents so it is visible
+    pub fn select_highlighted(&mut self) {
+        fn find(nodes: &mut [TreeNode]) -> bool {
+            for node in nodes.iter_mut() {
+                if node.highlight || find(&mut node.children) {
+                    if !node.highlight {
+                        node.expanded = true;
+                    }
+                    return true;
+                }
+            }
+            false
+        }
+        find(&mut self.items);
+        let selected = self.rows().iter().position(|row| row.node.highlight);
+        self.state.select(selected.or(Some(0)));
+    }
+
+    fn set_expanded(&mut self, expanded: Option<bool>) {
+        let path = match self.state.selected().and_then(|i| self.rows().get(i).map(|row| row.path.clone())) {
+            Some(path) => path,
+            None => return,
+        };
+        let mut nodes = &mut self.items;
+        for (depth, index) in path.iter().enumerate() {
+            let node = &mut nodes[*index];
+            if depth == path.len() - 1 {
+                if !node.children.is_empty() {
+                    node.expanded = expanded.unwrap_or(!node.expanded);
+                }
+                return;
+            }
+            nodes = &mut node.children;
+        }
+    }
+}
+
+fn push_rows<'a>(node: &'a TreeNode, path: Vec<usize>, rows: &mut Vec<TreeRow<'a>>) {
+    rows.push(TreeRow { depth: path.len() - 1, path: path.clone(), node });
+    if node.expanded {
+        for (i, child) in node.children.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.push(i);
+            push_rows(child, child_path, rows);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, SystemTime};
+use anyhow::{anyhow, Error};
+use notify::{Config, Event, PollWatcher, RecursiveMode, Watcher};
+use crate::batch::{build_id, Batch};
+use crate::callstack::Frame;
+use crate::clustering::{cluster, cluster_label, normalize, Cluster, DEFAULT_THRESHOLD};
+use crate::correlation::crash_factors;
+use crate::crash_db::CrashDb;
+use crate::crash_export::load_metrics;
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::graphics_settings::GraphicsSettings;
+use crate::scenes::SceneTable;
+use crate::timing::{crash_file_time, format_time, UptimeBucket};
+
+//where a crash of a corpus can be loaded from again
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrashOrigin {
+    File(PathBuf),
+    Database { db: PathBuf, id: i64 },
+}
+
+impl CrashOrigin {
+    pub fn load(&self) -> Result<(G2CrashMetrics, Option<SystemTime>), Error> {
+        match self {
+            CrashOrigin::File(path) => Ok((load_metrics(&path.to_string_lossy())?, crash_file_time(path))),
+            CrashOrigin::Database { db, id } => CrashDb::open(db)?.load_crash(*id),
+        }
+    }
+}
+
+impl fmt::Display for CrashOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrashOrigin::File(path) => write!(f, "{}", path.display()),
+            CrashOrigin::Database { db, id } => write!(f, "crash {id} of {}", db.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CorpusEntry {
+    pub origin: CrashOrigin,
+    pub time: String,
+    pub build: String,
+    pub scene: String,
+    pub exception: String,
+    pub signature: String,
+    pub module: String,
+    //vendor and driver version
+    pub gpu: String,
+    pub uptime_ms: u64,
+    //innermost first
+    pub frames: Vec<Frame>,
+    //normalized for clustering
+    pub stack: Vec<Frame>,
+    //label of the cluster the crash ended up in
+    pub cluster: String,
+    //settings and hardware as factor name and value, see correlation
+    pub factors: Vec<(String, String)>,
+}
+
+//the properties crashes are grouped by in the statistics tab
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dimension {
+    Exception,
+    Module,
+    Scene,
+    Gpu,
+    Build,
+    Uptime,
+    Cluster,
+}
+
+pub const DIMENSIONS: [Dimension; 7] = [
+    Dimension::Exception,
+    Dimension::Module,
+    Dimension::Scene,
+    Dimension::Gpu,
+    Dimension::Build,
+    Dimension::Uptime,
+    Dimension::Cluster,
+];
+
+impl Dimension {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dimension::Exception => "exception",
+            Dimension::Module => "module",
+            Dimension::Scene => "scene",
+            Dimension::Gpu => "gpu",
+            Dimension::Build => "build",
+            Dimension::Uptime => "uptime",
+            Dimension::Cluster => "cluster",
+        }
+    }
+
+    pub fn value(self, entry: &CorpusEntry) -> String {
+        match self {
+            Dimension::Exception => entry.exception.clone(),
+            Dimension::Module => entry.module.clone(),
+            Dimension::Scene => entry.scene.clone(),
+            Dimension::Gpu => entry.gpu.clone(),
+            Dimension::Build => entry.build.clone(),
+            Dimension::Uptime => UptimeBucket::from_uptime(entry.uptime_ms).to_string(),
+            Dimension::Cluster => entry.cluster.clone(),
+        }
+    }
+}
+
+//narrows a corpus down to the crashes sharing one value, e.g. a bar of the statistics tab
+#[derive(Debug, Clone, PartialEq)]
+pub struct Facet {
+    pub dimension: Dimension,
+    pub value: String,
+}
+
+impl Facet {
+    pub fn matches(&self, entry: &CorpusEntry) -> bool {
+        self.dimension.value(entry) == self.value
+    }
+}
+
+impl fmt::Display for Facet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.dimension.name(), self.value)
+    }
+}
+
+//the crashes of a folder or crash database, shared by the corpus and statistics tabs
+pub struct Corpus {
+    pub source: Option<PathBuf>,
+    pub entries: Vec<CorpusEntry>,
+    //files that looked like crashes but could not be read
+    pub failures: usize,
+    pub error: Option<String>,
+    pub cluster_threshold: f64,
+    pub clusters: Vec<Cluster>,
+    //bumped on every scan so tabs know when to rebuild their views
+    pub generation: u64,
+    //polls the folder or database so new crashes show up without a rescan
+    watcher: Option<PollWatcher>,
+    receiver: Option<Receiver<notify::Result<Event>>>,
+}
+
+impl Corpus {
+    pub fn new() -> Corpus {
+        Corpus {
+            source: None,
+            entries: vec![],
+            failures: 0,
+            error: None,
+            cluster_threshold: DEFAULT_THRESHOLD,
+            clusters: vec![],
+            generation: 0,
+            watcher: None,
+            receiver: None,
+        }
+    }
+
+    pub fn open(&mut self, source: PathBuf, scenes: &SceneTable) {
+        let config = Config::default().with_poll_interval(Duration::from_secs(2));
+        let (sender, receiver) = channel();
+        self.watcher = PollWatcher::new(sender, config).ok().and_then(|mut watcher| {
+            watcher.watch(&source, RecursiveMode::Recursive).ok().map(|_| watcher)
+        });
+        self.receiver = Some(receiver);
+        self.source = Some(source);
+        self.rescan(scenes);
+    }
+
+    //rescans when the watcher saw a change
+    pub fn poll(&mut self, scenes: &SceneTable) {
+        let changed = self.receiver.as_ref().map(|receiver| receiver.try_iter().count() > 0).unwrap_or(false);
+        if changed {
+            self.rescan(scenes);
+        }
+    }
+
+    fn rescan(&mut self, scenes: &SceneTable) {
+        let source = match &self.source {
+            Some(source) => source,
+            None => return,
+        };
+        match scan(source, scenes) {
+            Ok((entries, failures)) => {
+                self.entries = entries;
+                self.failures = failures;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to read {}: {e}", source.display())),
+        }
+        self.recluster();
+    }
+
+    pub fn set_cluster_threshold(&mut self, threshold: f64) {
+        self.cluster_threshold = threshold.clamp(0.0, 1.0);
+        self.recluster();
+    }
+
+    fn recluster(&mut self) {
+        self.clusters = cluster_entries(&mut self.entries, self.cluster_threshold);
+        self.generation += 1;
+    }
+}
+
+//clusters the entries and labels each with its cluster
+pub fn cluster_entries(entries: &mut [CorpusEntry], threshold: f64) -> Vec<Cluster> {
+    let stacks: Vec<Vec<Frame>> = entries.iter().map(|entry| entry.stack.clone()).collect();
+    let clusters = cluster(&stacks, threshold);
+    for (i, found) in clusters.iter().enumerate() {
+        let label = cluster_label(i + 1, &entries[found.representative].signature);
+        for member in &found.members {
+            entries[*member].cluster = label.clone();
+        }
+    }
+    clusters
+}
+
+//a directory is searched recursively, any other file is opened as a crash database
+pub fn scan(source: &Path, scenes: &SceneTable) -> Result<(Vec<CorpusEntry>, usize), Error> {
+    if source.is_dir() {
+        let batch = Batch::load_dir(source)?;
+        let entries = batch.crashes.iter()
+            .map(|crash| {
+                let export = crash.export(scenes);
+                let derived = &export.derived;
+                CorpusEntry {
+                    origin: CrashOrigin::File(crash.path.clone()),
+                    time: derived.timing.crash_time.as_ref().map(format_time).unwrap_or_default(),
+                    build: build_id(&derived.modules).unwrap_or_else(|| "unknown".to_string()),
+                    scene: derived.scene_info.as_ref().map(|info| info.to_string()).unwrap_or_else(|| crash.metrics.scene.to_string()),
+                    exception: format!("{} {}", derived.exception.code, derived.exception.name.as_deref().unwrap_or("")).trim_end().to_string(),
+                    signature: derived.signature.text.clone(),
+                    module: derived.faulting_module().unwrap_or_else(|| "unknown".to_string()),
+                    gpu: gpu_name(derived.system.gpu_vendor(), derived.system.driver_version.as_deref()),
+                    uptime_ms: derived.timing.uptime_ms,
+                    frames: derived.frames.clone(),
+                    stack: normalize(&derived.frames),
+                    cluster: String::new(),
+                    factors: crash_factors(
+                        GraphicsSettings::parse(&crash.metrics.settings_info.to_string()).entries.into_iter()
+                            .map(|setting| (setting.key, setting.value.to_string()))
+                            .collect(),
+                        derived.system.gpus.first().map(String::as_str),
+                        derived.system.driver_version.as_deref(),
+                        Some(&crash.metrics.operating_system.to_string()),
+                        derived.vr.runtime.is_some() || derived.vr.hmd_model.is_some(),
+                    ),
+                }
+            })
+            .collect();
+        Ok((entries, batch.failures.len()))
+    } else if source.is_file() {
+        Ok((CrashDb::open(source)?.summaries()?, 0))
+    } else {
+        Err(anyhow!("{} does not exist", source.display()))
+    }
+}
+
+pub fn gpu_name(vendor: Option<&str>, driver: Option<&str>) -> String {
+    match (vendor, driver) {
+        (Some(vendor), Some(driver)) => format!("{vendor} {driver}"),
+        (Some(vendor), None) => vendor.to_string(),
+        (None, Some(driver)) => format!("unknown {driver}"),
+        (None, None) => "unknown".to_string(),
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use crate::corpus::CorpusEntry;
+use crate::system_info::gpu_model;
+
+//false discovery rate a factor is flagged at
+pub const SIGNIFICANCE: f64 = 0.05;
+
+//the settings and hardware a crash happened with, as factor name and value
+pub fn crash_factors(settings: Vec<(String, String)>, gpu: Option<&str>, driver: Option<&str>, os: Option<&str>, vr: bool) -> Vec<(String, String)> {
+    let mut factors: Vec<(String, String)> = settings.into_iter()
+        .map(|(key, value)| (format!("setting {key}"), value))
+        .collect();
+    if let Some(gpu) = gpu {
+        factors.push(("gpu".to_string(), gpu_model(gpu).to_string()));
+    }
+    if let Some(driver) = driver {
+        factors.push(("driver".to_string(), driver.to_string()));
+    }
+    if let Some(os) = os.filter(|os| !os.is_empty()) {
+        factors.push(("os".to_string(), os.to_string()));
+    }
+    factors.push(("vr".to_string(), if vr { "yes" } else { "no" }.to_string()));
+    factors
+}
+
+#[derive(Debug, Clone)]
+pub struct Correlation {
+    pub cluster: String,
+    pub cluster_size: usize,
+    pub factor: String,
+    pub value: String,
+    //crashes of the cluster with the factor value
+    pub count: usize,
+    //crashes of the whole corpus with the factor value
+    pub corpus_count: usize,
+    //how much more common the value is in the cluster than in the corpus
+    pub lift: f64,
+    pub chi_square: f64,
+    //one-sided fisher's exact test, exact for the small counts of a crash corpus
+    pub p_value: f64,
+    //benjamini-hochberg adjusted over every cluster and value that was tested
+    pub adjusted_p: f64,
+}
+
+//every factor value over-represented in a cluster, the most suspicious first.
+//entries must have been clustered already
+pub fn correlate(entries: &[CorpusEntry], min_count: usize) -> Vec<Correlation> {
+    let total = entries.len();
+    let mut clusters: HashMap<&str, usize> = HashMap::new();
+    let mut factors: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut counts: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    for entry in entries {
+        *clusters.entry(&entry.cluster).or_default() += 1;
+        for (factor, value) in &entry.factors {
+            *factors.entry((factor, value)).or_default() += 1;
+            *counts.entry((&entry.cluster, factor, value)).or_default() += 1;
+        }
+    }
+
+    let log_factorials = log_factorials(total);
+    let mut correlations: Vec<Correlation> = counts.into_iter()
+        .filter(|(_, count)| *count >= min_count)
+        .map(|((cluster, factor, value), count)| {
+            let cluster_size = clusters[cluster];
+            let corpus_count = factors[&(factor, value)];
+            Correlation {
+                cluster: cluster.to_string(),
+                cluster_size,
+                factor: factor.to_string(),
+                value: value.to_string(),
+                count,
+                corpus_count,
+                lift: (count as f64 / cluster_size as f64) / (corpus_count as f64 / total as f64),
+                chi_square: chi_square(count, cluster_size, corpus_count, total),
+                p_value: fisher_exact(&log_factorials, count, cluster_size, corpus_count, total),
+                adjusted_p: 1.0,
+            }
+        })
+        .collect();
+    adjust(&mut correlations);
+
+    correlations.retain(|correlation| correlation.lift > 1.0);
+    correlations.sort_by(|a, b| a.adjusted_p.total_cmp(&b.adjusted_p)
+        .then(b.lift.total_cmp(&a.lift))
+        .then(b.count.cmp(&a.count))
+        .then_with(|| (&a.cluster, &a.factor, &a.value).cmp(&(&b.cluster, &b.factor, &b.value))));
+    correlations
+}
+
+//benjamini-hochberg, controls the share of false positives among the flagged factors
+fn adjust(correlations: &mut [Correlation]) {
+    let tests = correlations.len() as f64;
+    let mut order: Vec<usize> = (0..correlations.len()).collect();
+    order.sort_by(|a, b| correlations[*a].p_value.total_cmp(&correlations[*b].p_value));
+    let mut adjusted: f64 = 1.0;
+    for (rank, index) in order.iter().enumerate().rev() {
+        adjusted = adjusted.min(correlations[*index].p_value * tests / (rank + 1) as f64);
+        correlations[*index].adjusted_p = adjusted;
+    }
+}
+
+fn log_factorials(n: usize) -> Vec<f64> {
+    let mut table = vec![0.0; n + 1];
+    for i in 1..=n {
+        table[i] = table[i - 1] + (i as f64).ln();
+    }
+    table
+}
+
+//chance of at least count crashes of the cluster having the value if the value was spread at random
+fn fisher_exact(log_factorials: &[f64], count: usize, cluster_size: usize, corpus_count: usize, total: usize) -> f64 {
+    let choose = |n: usize, k: usize| log_factorials[n] - log_factorials[k] - log_factorials[n - k];
+    let denominator = choose(total, cluster_size);
+    let most = cluster_size.min(corpus_count);
+    let p: f64 = (count..=most)
+        .filter(|k| cluster_size - k <= total - corpus_count)
+        .map(|k| (choose(corpus_count, k) + choose(total - corpus_count, cluster_size - k) - denominator).exp())
+        .sum();
+    p.min(1.0)
+}
+
+//pearson's chi-square of the 2x2 table in cluster / not in cluster by with value / without value
+fn chi_square(count: usize, cluster_size: usize, corpus_count: usize, total: usize) -> f64 {
+    let (a, n, m, total) = (count as f64, cluster_size as f64, corpus_count as f64, total as f64);
+    let denominator = n * (total - n) * m * (total - m);
+    if denominator == 0.0 {
+        return 0.0;
+    }
+    let b = n - a;
+    let c = m - a;
+    let d = total - n - m + a;
+    total * (a * d - b * c).powi(2) / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use super::*;
+    use crate::corpus::CrashOrigin;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn correlation(p_value: f64) -> Correlation {
+        Correlation {
+            cluster: String::new(),
+            cluster_size: 0,
+            factor: String::new(),
+            value: String::new(),
+            count: 0,
+            corpus_count: 0,
+            lift: 1.0,
+            chi_square: 0.0,
+            p_value,
+            adjusted_p: 1.0,
+        }
+    }
+
+    fn entry(cluster: &str, ssao: &str) -> CorpusEntry {
+        CorpusEntry {
+            origin: CrashOrigin::File(PathBuf::new()),
+            time: String::new(),
+            build: String::new(),
+            scene: String::new(),
+            exception: String::new(),
+            signature: String::new(),
+            module: String::new(),
+            gpu: String::new(),
+            uptime_ms: 0,
+            frames: vec![],
+            stack: vec![],
+            cluster: cluster.to_string(),
+            factors: crash_factors(vec![("SSAO".to_string(), ssao.to_string())], None, None, None, false),
+        }
+    }
+
+    #[test]
+    fn fisher_exact_matches_the_hypergeometric_tail() {
+        let table = log_factorials(8);
+        //the lady tasting tea: all 4 of 4 right is 1 in 70, at least 3 of 4 is 17 in 70
+        assert!(close(fisher_exact(&table, 4, 4, 4, 8), 1.0 / 70.0));
+        assert!(close(fisher_exact(&table, 3, 4, 4, 8), 17.0 / 70.0));
+        assert!(close(fisher_exact(&table, 0, 4, 4, 8), 1.0));
+    }
+
+    #[test]
+    fn chi_square_of_a_2x2_table() {
+        assert!(close(chi_square(3, 3, 3, 6), 6.0));
+        assert!(close(chi_square(2, 4, 4, 8), 0.0));
+        //a value every crash has says nothing
+        assert_eq!(chi_square(3, 3, 6, 6), 0.0);
+    }
+
+    #[test]
+    fn benjamini_hochberg_adjustment() {
+        let mut correlations: Vec<Correlation> = [0.01, 0.04, 0.03, 0.5].into_iter().map(correlation).collect();
+        adjust(&mut correlations);
+        let adjusted: Vec<f64> = correlations.iter().map(|correlation| correlation.adjusted_p).collect();
+        let expected = [0.04, 0.16 / 3.0, 0.16 / 3.0, 0.5];
+        assert!(adjusted.iter().zip(expected).all(|(a, b)| close(*a, b)), "{adjusted:?}");
+    }
+
+    #[test]
+    fn over_represented_values_are_ranked_first() {
+        let mut entries: Vec<CorpusEntry> = (0..4).map(|_| entry("#1", "true")).collect();
+        entries.extend((0..4).map(|_| entry("#2", "false")));
+        let correlations = correlate(&entries, 2);
+        let top = &correlations[0];
+        assert_eq!((top.factor.as_str(), top.count, top.corpus_count), ("setting SSAO", 4, 4));
+        assert!(close(top.lift, 2.0));
+        assert!(close(top.p_value, 1.0 / 70.0));
+        //vr is "no" everywhere, so it is never over-represented
+        assert!(correlations.iter().all(|correlation| correlation.factor != "vr"));
+    }
+}
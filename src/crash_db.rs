@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use anyhow::{anyhow, Error};
+use chrono::Local;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use sha2::{Digest, Sha256};
+use crate::batch::build_id;
+use crate::callstack::Frame;
+use crate::clustering::normalize;
+use crate::corpus::{gpu_name, CorpusEntry, CrashOrigin};
+use crate::correlation::crash_factors;
+use crate::crash_export::{import_metrics, CrashExport};
+use crate::exporters::ExportFormat;
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::graphics_settings::GraphicsSettings;
+use crate::scenes::SceneTable;
+use crate::timing::{crash_file_time, format_time, parse_time};
+
+pub const DEFAULT_DB: &str = "crashes.db";
+
+//blobs keeps every ingested file exactly as it was read, everything else can be rebuilt from them.
+//sha256 is taken over those bytes, canonical_sha256 over the decrypted crash with zeroed padding,
+//so a crash and its export are two blobs but one crash
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS blobs (
+    sha256 TEXT PRIMARY KEY,
+    canonical_sha256 TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    data BLOB NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sources (
+    sha256 TEXT NOT NULL REFERENCES blobs(sha256),
+    path TEXT NOT NULL,
+    ingested_at TEXT NOT NULL,
+    UNIQUE (sha256, path)
+);
+CREATE TABLE IF NOT EXISTS crashes (
+    id INTEGER PRIMARY KEY,
+    sha256 TEXT NOT NULL REFERENCES blobs(sha256),
+    canonical_sha256 TEXT NOT NULL UNIQUE,
+    crash_time TEXT,
+    ingested_at TEXT NOT NULL,
+    scene TEXT NOT NULL,
+    location TEXT,
+    uptime_ms INTEGER NOT NULL,
+    uptime_bucket TEXT NOT NULL,
+    exception_code TEXT NOT NULL,
+    exception_name TEXT,
+    faulting_module TEXT,
+    signature TEXT NOT NULL,
+    signature_id TEXT NOT NULL,
+    build TEXT,
+    gpu_vendor TEXT,
+    server_kind TEXT NOT NULL,
+    vr_headset TEXT,
+    export TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS modules (
+    crash_id INTEGER NOT NULL REFERENCES crashes(id),
+    idx INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    base INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    pdb_guid TEXT NOT NULL,
+    pdb_age TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS frames (
+    crash_id INTEGER NOT NULL REFERENCES crashes(id),
+    idx INTEGER NOT NULL,
+    address INTEGER NOT NULL,
+    module TEXT,
+    offset INTEGER
+);
+CREATE TABLE IF NOT EXISTS settings (
+    crash_id INTEGER NOT NULL REFERENCES crashes(id),
+    key TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS blobs_canonical ON blobs(canonical_sha256);
+CREATE INDEX IF NOT EXISTS crashes_signature ON crashes(signature_id);
+CREATE INDEX IF NOT EXISTS modules_crash ON modules(crash_id);
+CREATE INDEX IF NOT EXISTS frames_crash ON frames(crash_id);
+CREATE INDEX IF NOT EXISTS settings_crash ON settings(crash_id);
+"#;
+
+//name, description and sql of the canned reports the query command accepts instead of sql
+pub const REPORTS: [(&str, &str, &str); 7] = [
+    ("signatures", "crashes per signature",
+        "SELECT signature, COUNT(*) AS crashes, MIN(crash_time) AS first_seen, MAX(crash_time) AS last_seen FROM crashes GROUP BY signature_id ORDER BY crashes DESC, signature"),
+    ("builds", "crashes per game build",
+        "SELECT COALESCE(build, 'unknown') AS build, COUNT(*) AS crashes, COUNT(DISTINCT signature_id) AS signatures FROM crashes GROUP BY build ORDER BY crashes DESC"),
+    ("modules", "crashes per faulting module",
+        "SELECT COALESCE(faulting_module, 'unknown') AS module, COUNT(*) AS crashes, COUNT(DISTINCT signature_id) AS signatures FROM crashes GROUP BY faulting_module ORDER BY crashes DESC"),
+    ("scenes", "crashes per location",
+        "SELECT COALESCE(location, scene) AS location, COUNT(*) AS crashes FROM crashes GROUP BY COALESCE(location, scene) ORDER BY crashes DESC"),
+    ("settings", "how often each graphics setting value occurs in crashes",
+        "SELECT key, value, COUNT(*) AS crashes FROM settings GROUP BY key, value ORDER BY key, crashes DESC"),
+    ("recent", "the 20 most recent crashes",
+        "SELECT id, crash_time, signature, COALESCE(location, scene) AS location, uptime_bucket FROM crashes ORDER BY crash_time DESC, id DESC LIMIT 20"),
+    ("duplicates", "crashes that were ingested from more than one path or file",
+        "SELECT c.id, c.signature, COUNT(*) AS copies, GROUP_CONCAT(s.path, '; ') AS paths FROM sources s JOIN blobs b ON b.sha256 = s.sha256 JOIN crashes c ON c.canonical_sha256 = b.canonical_sha256 GROUP BY c.id HAVING copies > 1 ORDER BY copies DESC"),
+];
+
+pub enum Ingested {
+    New(i64),
+    //the same crash was ingested before, possibly from another path, machine or export
+    Duplicate(i64),
+}
+
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+pub struct CrashDb {
+    path: PathBuf,
+    connection: Connection,
+}
+
+impl CrashDb {
+    //read only, queries and the corpus can't change the crashes by accident
+    pub fn open(path: &Path) -> Result<CrashDb, Error> {
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let tables: i64 = connection.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'crashes'", [], |row| row.get(0))?;
+        if tables == 0 {
+            return Err(anyhow!("{} is not a crash database", path.display()));
+        }
+        Ok(CrashDb { path: path.to_path_buf(), connection })
+    }
+
+    //creates the database and its tables when they don't exist yet
+    pub fn open_for_ingest(path: &Path) -> Result<CrashDb, Error> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(CrashDb { path: path.to_path_buf(), connection })
+    }
+
+    pub fn ingest(&mut self, path: &Path, scenes: &SceneTable) -> Result<Ingested, Error> {
+        let bytes = std::fs::read(path)?;
+        let sha256 = format!("{:x}", Sha256::digest(&bytes));
+        let ingested_at = format_time(&Local::now());
+        let source = path.display().to_string();
+
+        let existing: Option<i64> = self.connection
+            .query_row("SELECT c.id FROM blobs b JOIN crashes c ON c.canonical_sha256 = b.canonical_sha256 WHERE b.sha256 = ?1", params![sha256], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            self.connection.execute("INSERT OR IGNORE INTO sources (sha256, path, ingested_at) VALUES (?1, ?2, ?3)", params![sha256, source, ingested_at])?;
+            return Ok(Ingested::Duplicate(id));
+        }
+
+        //parse before writing anything so a broken file leaves no trace
+        let (metrics, kind) = match ExportFormat::from_path(path) {
+            Some(format) => (import_metrics(&bytes, format)?, format.extension()),
+            None => (G2CrashMetrics::from_bytes(bytes.clone())?, "dat"),
+        };
+        let canonical_sha256 = format!("{:x}", Sha256::digest(metrics.to_bytes()?));
+
+        //a crash ingested before from another file, e.g. the export of a crash_metrics.dat
+        let existing: Option<i64> = self.connection
+            .query_row("SELECT id FROM crashes WHERE canonical_sha256 = ?1", params![canonical_sha256], |row| row.get(0))
+            .optional()?;
+        if let Some(id) = existing {
+            let transaction = self.connection.transaction()?;
+            transaction.execute("INSERT INTO blobs (sha256, canonical_sha256, kind, data) VALUES (?1, ?2, ?3, ?4)", params![sha256, canonical_sha256, kind, bytes])?;
+            transaction.execute("INSERT INTO sources (sha256, path, ingested_at) VALUES (?1, ?2, ?3)", params![sha256, source, ingested_at])?;
+            transaction.commit()?;
+            return Ok(Ingested::Duplicate(id));
+        }
+
+        let export = CrashExport::new(&metrics, scenes, crash_file_time(path));
+        let derived = &export.derived;
+
+        let transaction = self.connection.transaction()?;
+        transaction.execute("INSERT INTO blobs (sha256, canonical_sha256, kind, data) VALUES (?1, ?2, ?3, ?4)", params![sha256, canonical_sha256, kind, bytes])?;
+        transaction.execute("INSERT INTO sources (sha256, path, ingested_at) VALUES (?1, ?2, ?3)", params![sha256, source, ingested_at])?;
+        transaction.execute(
+            "INSERT INTO crashes (sha256, canonical_sha256, crash_time, ingested_at, scene, location, uptime_ms, uptime_bucket, exception_code, exception_name, faulting_module, signature, signature_id, build, gpu_vendor, server_kind, vr_headset, export)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+            params![
+                sha256,
+                canonical_sha256,
+                derived.timing.crash_time.as_ref().map(format_time),
+                ingested_at,
+                metrics.scene.to_string(),
+                derived.scene_info.as_ref().map(|info| info.to_string()),
+                derived.timing.uptime_ms as i64,
+                derived.timing.bucket.to_string(),
+                derived.exception.code,
+                derived.exception.name,
+                derived.faulting_module(),
+                derived.signature.text,
+                derived.signature.id,
+                build_id(&derived.modules),
+                derived.system.gpu_vendor(),
+                derived.online.server_kind.to_string(),
+                derived.vr.hmd_model,
+                serde_json::to_string(&export)?,
+            ],
+        )?;
+        let id = transaction.last_insert_rowid();
+
+        for (i, module) in derived.modules.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO modules (crash_id, idx, name, base, size, pdb_guid, pdb_age) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![id, i as i64, module.name, module.base as i64, module.size as i64, module.pdb_guid, module.pdb_age],
+            )?;
+        }
+        for (i, frame) in derived.frames.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO frames (crash_id, idx, address, module, offset) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, i as i64, frame.address as i64, frame.module, frame.offset.map(|offset| offset as i64)],
+            )?;
+        }
+        for setting in GraphicsSettings::parse(&metrics.settings_info.to_string()).entries {
+            transaction.execute("INSERT INTO settings (crash_id, key, value) VALUES (?1, ?2, ?3)", params![id, setting.key, setting.value.to_string()])?;
+        }
+        transaction.commit()?;
+        Ok(Ingested::New(id))
+    }
+
+    pub fn summaries(&self) -> Result<Vec<CorpusEntry>, Error> {
+        let mut statement = self.connection.prepare("SELECT crash_id, key, value FROM settings ORDER BY crash_id, rowid")?;
+        let mut settings: HashMap<i64, Vec<(String, String)>> = HashMap::new();
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            settings.entry(row.get(0)?).or_default().push((row.get(1)?, row.get(2)?));
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT id, crash_time, COALESCE(build, 'unknown'), COALESCE(location, scene), exception_code, exception_name, signature,
+             COALESCE(faulting_module, 'unknown'), gpu_vendor, json_extract(export, '$.derived.system.driver_version'), uptime_ms,
+             json_extract(export, '$.derived.system.gpus[0]'), json_extract(export, '$.raw.operating_system'),
+             json_extract(export, '$.derived.vr.runtime') IS NOT NULL OR vr_headset IS NOT NULL FROM crashes ORDER BY id")?;
+        let entries = statement.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let code: String = row.get(4)?;
+            let name: Option<String> = row.get(5)?;
+            Ok(CorpusEntry {
+                origin: CrashOrigin::Database { db: self.path.clone(), id },
+                time: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                build: row.get(2)?,
+                scene: row.get(3)?,
+                exception: format!("{code} {}", name.unwrap_or_default()).trim_end().to_string(),
+                signature: row.get(6)?,
+                module: row.get(7)?,
+                gpu: gpu_name(row.get::<_, Option<String>>(8)?.as_deref(), row.get::<_, Option<String>>(9)?.as_deref()),
+                uptime_ms: row.get::<_, i64>(10)? as u64,
+                frames: vec![],
+                stack: vec![],
+                cluster: String::new(),
+                factors: crash_factors(
+                    settings.remove(&id).unwrap_or_default(),
+                    row.get::<_, Option<String>>(11)?.as_deref(),
+                    row.get::<_, Option<String>>(9)?.as_deref(),
+                    row.get::<_, Option<String>>(12)?.as_deref(),
+                    row.get(13)?,
+                ),
+            })
+        })?;
+        let mut entries: Vec<CorpusEntry> = entries.collect::<Result<_, _>>()?;
+
+        let mut statement = self.connection.prepare("SELECT crash_id, address, module, offset FROM frames ORDER BY crash_id, idx")?;
+        let mut frames: HashMap<i64, Vec<Frame>> = HashMap::new();
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            frames.entry(row.get(0)?).or_default().push(Frame {
+                address: row.get::<_, i64>(1)? as u64,
+                module: row.get(2)?,
+                offset: row.get::<_, Option<i64>>(3)?.map(|offset| offset as u64),
+            });
+        }
+        for entry in entries.iter_mut() {
+            if let CrashOrigin::Database { id, .. } = &entry.origin {
+                entry.frames = frames.remove(id).unwrap_or_default();
+                entry.stack = normalize(&entry.frames);
+            }
+        }
+        Ok(entries)
+    }
+
+    //rebuilt from the first file the crash was ingested from, not from the exported json
+    pub fn load_crash(&self, id: i64) -> Result<(G2CrashMetrics, Option<SystemTime>), Error> {
+        let (kind, data, crash_time): (String, Vec<u8>, Option<String>) = self.connection.query_row(
+            "SELECT b.kind, b.data, c.crash_time FROM crashes c JOIN blobs b ON b.sha256 = c.sha256 WHERE c.id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional()?.ok_or_else(|| anyhow!("there is no crash {id}"))?;
+        let metrics = match ExportFormat::from_name(&kind) {
+            Some(format) => import_metrics(&data, format)?,
+            None => G2CrashMetrics::from_bytes(data)?,
+        };
+        Ok((metrics, crash_time.as_deref().and_then(parse_time)))
+    }
+
+    //sql is either the name of a canned report or a statement of its own
+    pub fn query(&self, sql: &str) -> Result<QueryResult, Error> {
+        let sql = REPORTS.iter().find(|(name, _, _)| *name == sql.trim()).map(|(_, _, sql)| *sql).unwrap_or(sql);
+        let mut statement = self.connection.prepare(sql)?;
+        let columns: Vec<String> = statement.column_names().into_iter().map(str::to_string).collect();
+        let mut rows = vec![];
+        let mut result = statement.query([])?;
+        while let Some(row) = result.next()? {
+            let mut values = vec![];
+            for i in 0..columns.len() {
+                values.push(match row.get_ref(i)? {
+                    ValueRef::Null => String::new(),
+                    ValueRef::Integer(value) => value.to_string(),
+                    ValueRef::Real(value) => value.to_string(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).to_string(),
+                    ValueRef::Blob(bytes) => format!("<{} bytes>", bytes.len()),
+                });
+            }
+            rows.push(values);
+        }
+        Ok(QueryResult { columns, rows })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::g2_crash_metrics::NullStringS;
+
+    fn crash(scene: &str) -> G2CrashMetrics {
+        G2CrashMetrics {
+            scene: NullStringS::from_str(scene).unwrap(),
+            uptimems: 90_000,
+            ..Default::default()
+        }
+    }
+
+    fn count(db: &CrashDb, table: &str) -> String {
+        db.query(&format!("SELECT COUNT(*) FROM {table}")).unwrap().rows[0][0].clone()
+    }
+
+    #[test]
+    fn ingest_keeps_the_original_bytes_and_dedups_by_crash() {
+        let dir = std::env::temp_dir().join(format!("crash-db-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let metrics = crash("assembly:/_pro/scenes/missions/paris/_scene_paris.entity");
+        //garbage after the scene's terminator, the game doesn't zero its padding either
+        let mut original = metrics.to_bytes().unwrap();
+        original[16 + 0xF0] ^= 0x5A;
+        let dat = dir.join("crash_metrics.dat");
+        std::fs::write(&dat, &original).unwrap();
+        let copy = dir.join("copy.dat");
+        std::fs::write(&copy, &original).unwrap();
+        let export = dir.join("crash.json");
+        let bytes = ExportFormat::Json.write(&[CrashExport::new(&metrics, &SceneTable::bundled(), None)]).unwrap();
+        std::fs::write(&export, bytes).unwrap();
+        let other = dir.join("other.dat");
+        std::fs::write(&other, crash("assembly:/_pro/scenes/missions/miami/_scene_miami.entity").to_bytes().unwrap()).unwrap();
+
+        let mut db = CrashDb::open_for_ingest(&dir.join("crashes.db")).unwrap();
+        let scenes = SceneTable::bundled();
+        let Ingested::New(id) = db.ingest(&dat, &scenes).unwrap() else { panic!("first ingest is new") };
+        assert!(matches!(db.ingest(&copy, &scenes).unwrap(), Ingested::Duplicate(duplicate) if duplicate == id));
+        assert!(matches!(db.ingest(&export, &scenes).unwrap(), Ingested::Duplicate(duplicate) if duplicate == id));
+        assert!(matches!(db.ingest(&other, &scenes).unwrap(), Ingested::New(new) if new != id));
+
+        let sha256 = format!("{:x}", Sha256::digest(&original));
+        let (data, canonical): (Vec<u8>, String) = db.connection
+            .query_row("SELECT data, canonical_sha256 FROM blobs WHERE sha256 = ?1", params![sha256], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(data, original);
+        assert_ne!(canonical, sha256);
+        assert_eq!(count(&db, "crashes"), "2");
+        assert_eq!(count(&db, "blobs"), "3");
+        assert_eq!(count(&db, "sources"), "4");
+        let duplicates = db.query("duplicates").unwrap();
+        assert_eq!(duplicates.rows.len(), 1);
+        assert_eq!(duplicates.rows[0][2], "3");
+
+        let (loaded, _) = db.load_crash(id).unwrap();
+        assert_eq!(loaded.scene.to_string(), metrics.scene.to_string());
+        drop(db);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
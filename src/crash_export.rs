@@ -0,0 +1,202 @@
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::callstack::{parse_callstack, Frame, Signature};
+use crate::exception_record::{decode_flags, ExceptionDetails};
+use crate::exporters::ExportFormat;
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::modules::{find_module, parse_modules, Module};
+use crate::online::OnlineInfo;
+use crate::scenes::{SceneInfo, SceneTable};
+use crate::status_codes::{CodeKind, DecodedCode};
+use crate::system_info::SystemInfo;
+use crate::timing::CrashTiming;
+use crate::vr_info::VrInfo;
+
+//version 1 is the unversioned export with the raw fields at the top level
+pub const SCHEMA_VERSION: u32 = 2;
+
+//the raw metrics next to everything derived from them, import only reads the raw fields back
+#[derive(Serialize, JsonSchema)]
+pub struct CrashExport<'a> {
+    pub schema_version: u32,
+    //callstack cluster within the exported crashes, only set when several crashes are exported together
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<String>,
+    pub raw: &'a G2CrashMetrics,
+    pub derived: DerivedData,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct DerivedData {
+    pub scene_info: Option<SceneInfo>,
+    pub system: SystemInfo,
+    pub online: OnlineInfo,
+    pub vr: VrInfo,
+    pub timing: CrashTiming,
+    pub modules: Vec<Module>,
+    pub frames: Vec<Frame>,
+    pub exception: ExceptionSummary,
+    pub signature: Signature,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ExceptionSummary {
+    pub code: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub flags: Vec<String>,
+    pub address: String,
+    //module relative address when it falls inside a loaded module
+    pub location: Option<String>,
+    pub interpretation: Vec<String>,
+}
+
+impl ExceptionSummary {
+    pub fn new(metrics: &G2CrashMetrics, modules: &[Module]) -> ExceptionSummary {
+        let exception = &metrics.exception;
+        let code = DecodedCode::new(exception.exception_code, CodeKind::NtStatus);
+        ExceptionSummary {
+            code: format!("0x{:08X}", exception.exception_code),
+            name: code.name().map(str::to_string),
+            description: code.description().map(str::to_string),
+            flags: decode_flags(exception.exception_flags).into_iter().map(str::to_string).collect(),
+            address: format!("0x{:x}", exception.exception_address),
+            location: find_module(modules, exception.exception_address)
+                .map(|(module, offset)| format!("{}+0x{offset:x}", module.name)),
+            interpretation: ExceptionDetails::new(exception, modules).lines(),
+        }
+    }
+}
+
+impl<'a> CrashExport<'a> {
+    pub fn new(metrics: &'a G2CrashMetrics, scenes: &SceneTable, crash_time: Option<SystemTime>) -> CrashExport<'a> {
+        let modules = parse_modules(&metrics.modules.to_string());
+        let frames = parse_callstack(&metrics.callstack.to_string(), &modules);
+        CrashExport {
+            schema_version: SCHEMA_VERSION,
+            cluster: None,
+            raw: metrics,
+            derived: DerivedData {
+                scene_info: scenes.lookup(&metrics.scene.to_string()),
+                system: SystemInfo::parse(&metrics.system_info.to_string()),
+                online: OnlineInfo::parse(&metrics.net_role.to_string(), &metrics.online_server_version.to_string()),
+                vr: VrInfo::parse(&metrics.vr_data.to_string(), &metrics.vr_hdm_description.to_string()),
+                timing: CrashTiming::new(metrics.uptimems, crash_time),
+                exception: ExceptionSummary::new(metrics, &modules),
+                signature: Signature::new(metrics.exception.exception_code, &frames),
+                modules,
+                frames,
+            },
+        }
+    }
+}
+
+impl DerivedData {
+    //the module the exception address falls into, else the innermost resolved frame
+    pub fn faulting_module(&self) -> Option<String> {
+        match &self.exception.location {
+            Some(location) => location.split('+').next().map(str::to_string),
+            None => self.frames.iter().find_map(|frame| frame.module.clone()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Versioned {
+    schema_version: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RawDocument {
+    raw: Option<G2CrashMetrics>,
+}
+
+#[derive(Deserialize)]
+struct TimingDocument {
+    derived: Option<SavedDerived>,
+}
+
+#[derive(Deserialize)]
+struct SavedDerived {
+    timing: Option<SavedTiming>,
+}
+
+#[derive(Deserialize)]
+struct SavedTiming {
+    crash_time: Option<DateTime<Local>>,
+}
+
+//the crash time an export was saved with, older exports don't have one
+pub fn exported_crash_time(bytes: &[u8], format: ExportFormat) -> Option<SystemTime> {
+    let document: TimingDocument = decode(bytes, format).ok()?;
+    document.derived?.timing?.crash_time.map(SystemTime::from)
+}
+
+//an encrypted crash_metrics.dat, or a crash exported to any format that can be read back
+pub fn load_metrics(path: &str) -> Result<G2CrashMetrics, Error> {
+    match ExportFormat::from_path(Path::new(path)) {
+        Some(format) => import_metrics(&fs::read(path)?, format),
+        None => G2CrashMetrics::new(path),
+    }
+}
+
+//reads the raw fields of any schema version back
+pub fn import_metrics(bytes: &[u8], format: ExportFormat) -> Result<G2CrashMetrics, Error> {
+    let version: Versioned = decode(bytes, format)?;
+    match version.schema_version {
+        //older exports have the raw fields at the top level, anything else isn't a crash
+        None => decode(bytes, format).map_err(|e| anyhow!("not a crash export: {e}")),
+        Some(2) => decode::<RawDocument>(bytes, format)?.raw.ok_or_else(|| anyhow!("schema version 2 export without raw fields")),
+        Some(version) => Err(anyhow!("unsupported export schema version {version}, this build reads up to {SCHEMA_VERSION}")),
+    }
+}
+
+//typed instead of through serde_json::Value, which can't hold the byte strings of cbor and msgpack
+fn decode<T: DeserializeOwned>(bytes: &[u8], format: ExportFormat) -> Result<T, Error> {
+    Ok(match format {
+        ExportFormat::Json | ExportFormat::PrettyJson => serde_json::from_slice(bytes)?,
+        ExportFormat::Yaml => serde_yaml::from_slice(bytes)?,
+        ExportFormat::Toml => toml::from_str(std::str::from_utf8(bytes)?)?,
+        ExportFormat::Cbor => ciborium::de::from_reader(bytes)?,
+        ExportFormat::MessagePack => rmp_serde::from_slice(bytes)?,
+        ExportFormat::Csv | ExportFormat::Html => return Err(anyhow!("{} exports can't be imported", format.name())),
+    })
+}
+
+pub fn json_schema() -> String {
+    let schema = schemars::schema_for!(CrashExport);
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::g2_crash_metrics::NullStringS;
+
+    #[test]
+    fn documents_without_crash_fields_are_rejected() {
+        assert!(import_metrics(br#"{"title": "not a crash"}"#, ExportFormat::Json).is_err());
+        assert!(import_metrics(json_schema().as_bytes(), ExportFormat::Json).is_err());
+        assert!(import_metrics(br#"{"schema_version": 2}"#, ExportFormat::Json).is_err());
+        assert!(import_metrics(br#"{"schema_version": 3, "raw": {}}"#, ExportFormat::Json).is_err());
+    }
+
+    #[test]
+    fn unversioned_exports_are_read() {
+        let metrics = G2CrashMetrics {
+            uptimems: 1234,
+            scene: NullStringS::from_str("assembly:/_pro/scenes/missions/paris/_scene_paris.entity").unwrap(),
+            ..Default::default()
+        };
+        let unversioned = serde_json::to_vec(&metrics).unwrap();
+        let imported = import_metrics(&unversioned, ExportFormat::Json).unwrap();
+        assert_eq!(imported.uptimems, 1234);
+        assert_eq!(imported.scene.to_string(), metrics.scene.to_string());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{app::App, ui};
+use crate::runtime_id::HashList;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
@@ -10,6 +11,7 @@ use std::{
     time::{Duration, Instant},
 };
 use std::io::Stdout;
+use std::path::{Path, PathBuf};
 use anyhow::anyhow;
 use crossterm::event::KeyEventKind;
 use tui::{
@@ -17,15 +19,22 @@ use tui::{
     Terminal,
 };
 
-pub fn run(tick_rate: Duration, enhanced_graphics: bool, metrics_path: Option<String>) -> Result<(), Box<dyn Error>> {
+pub fn run(tick_rate: Duration, enhanced_graphics: bool, metrics_path: Option<String>, hash_list: Option<String>, corpus: Option<String>) -> Result<(), Box<dyn Error>> {
+    //anything that can fail goes before the terminal is switched to raw mode
+    let mut app = App::new("Hitman 3 crash inspector", enhanced_graphics);
+    if let Some(path) = hash_list {
+        app.data.hash_list = Some(HashList::load(Path::new(&path)).map_err(|e| anyhow!("failed to read the hash list {path}: {e}"))?);
+    }
+    if let Some(path) = corpus {
+        app.data.corpus.open(PathBuf::from(path), &app.data.scenes);
+    }
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-
-    let app = App::new("Hitman 3 crash inspector", enhanced_graphics);
     let res = run_app(&mut terminal, app, tick_rate, metrics_path);
 
     // restore terminal
@@ -75,6 +84,7 @@ fn run_app(
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     match key.code {
+                        _ if app.captures_input() => app.on_key(key.code),
                         KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('c') => {
                             app.data.should_quit = true;
                         }
@@ -0,0 +1,157 @@
+use std::fmt::Write;
+use std::path::Path;
+use anyhow::Error;
+use crate::batch::{build_id, count_by, Batch};
+use crate::crash_export::CrashExport;
+use crate::html_report::{escape, render_page, STYLE};
+use crate::rules::RuleSet;
+use crate::scenes::SceneTable;
+use crate::system_info::{format_size_mb, gpu_model};
+use crate::timing::format_time;
+
+const CRASH_DIR: &str = "crashes";
+
+const DASHBOARD_STYLE: &str = r#"
+.charts { display: grid; grid-template-columns: repeat(auto-fill, minmax(28em, 1fr)); gap: 1em 2em; }
+.bars td { border: none; padding: .1em .4em; }
+.bars td.label { width: 50%; overflow-wrap: anywhere; }
+.bar { background: #c00; height: 1em; min-width: 2px; }
+th.sortable { cursor: pointer; user-select: none; }
+th.sortable::after { content: " \2195"; color: #aaa; }
+nav { margin-bottom: 1em; }
+"#;
+
+//plain dom sorting, the site has to work from file:// without any server or external script
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("th").forEach(function (th, column) {
+    th.classList.add("sortable");
+    th.addEventListener("click", function () {
+      var ascending = th.dataset.order !== "asc";
+      th.dataset.order = ascending ? "asc" : "desc";
+      var rows = Array.from(table.querySelectorAll("tr")).slice(1);
+      rows.sort(function (a, b) {
+        var x = a.children[column].dataset.sort || a.children[column].textContent;
+        var y = b.children[column].dataset.sort || b.children[column].textContent;
+        var nx = parseFloat(x), ny = parseFloat(y);
+        var order = (!isNaN(nx) && !isNaN(ny)) ? nx - ny : x.localeCompare(y);
+        return ascending ? order : -order;
+      });
+      rows.forEach(function (row) { table.appendChild(row); });
+    });
+  });
+});
+"#;
+
+//the per crash values the index page groups and lists crashes by
+struct CrashRow {
+    page: String,
+    time: String,
+    timestamp: i64,
+    signature: String,
+    scene: String,
+    build: String,
+    module: String,
+    gpu_vendor: String,
+    gpu: String,
+    ram: String,
+    uptime_ms: u64,
+    uptime: String,
+    bucket: String,
+    vr_runtime: String,
+    vr_headset: String,
+    //names of the rules the crash matched
+    diagnoses: Vec<String>,
+}
+
+impl CrashRow {
+    fn new(page: String, export: &CrashExport, rules: &RuleSet) -> CrashRow {
+        let derived = &export.derived;
+        CrashRow {
+            page,
+            time: derived.timing.crash_time.as_ref().map(format_time).unwrap_or_default(),
+            timestamp: derived.timing.crash_time.map(|time| time.timestamp()).unwrap_or_default(),
+            signature: derived.signature.text.clone(),
+            scene: derived.scene_info.as_ref().map(|info| info.to_string()).unwrap_or_else(|| export.raw.scene.to_string()),
+            build: build_id(&derived.modules).unwrap_or_else(|| "unknown".to_string()),
+            module: derived.faulting_module().unwrap_or_else(|| "unknown".to_string()),
+            gpu_vendor: derived.system.gpu_vendor().unwrap_or("unknown").to_string(),
+            gpu: derived.system.gpus.first().map(|gpu| gpu_model(gpu).to_string()).unwrap_or_else(|| "unknown".to_string()),
+            ram: derived.system.ram_mb.map(format_size_mb).unwrap_or_else(|| "unknown".to_string()),
+            uptime_ms: derived.timing.uptime_ms,
+            uptime: derived.timing.uptime.clone(),
+            bucket: derived.timing.bucket.to_string(),
+            vr_runtime: derived.vr.runtime.clone().unwrap_or_else(|| "none".to_string()),
+            vr_headset: derived.vr.hmd_model.clone().unwrap_or_else(|| "none".to_string()),
+            diagnoses: rules.diagnose(export).iter().map(|rule| rule.name.clone()).collect(),
+        }
+    }
+}
+
+//writes index.html and one page per crash, returns the number of crash pages
+pub fn write_site(batch: &Batch, scenes: &SceneTable, rules: &RuleSet, output: &Path) -> Result<usize, Error> {
+    std::fs::create_dir_all(output.join(CRASH_DIR))?;
+
+    let mut rows = vec![];
+    for (i, crash) in batch.crashes.iter().enumerate() {
+        let export = crash.export(scenes);
+        let page = format!("{CRASH_DIR}/{:04}-{}.html", i + 1, export.derived.signature.id);
+        let nav = format!("<a href=\"../index.html\">&larr; dashboard</a> &middot; <span class=\"muted\">{}</span>", escape(&crash.path.display().to_string()));
+        std::fs::write(output.join(&page), render_page(std::slice::from_ref(&export), Some(&nav)))?;
+        rows.push(CrashRow::new(page, &export, rules));
+    }
+
+    std::fs::write(output.join("index.html"), render_index(&rows, batch))?;
+    Ok(rows.len())
+}
+
+fn render_index(rows: &[CrashRow], batch: &Batch) -> String {
+    let mut html = String::new();
+    let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Crash dashboard</title>\n<style>{STYLE}{DASHBOARD_STYLE}</style>\n</head>\n<body>\n");
+    let _ = writeln!(html, "<h1>Crash dashboard</h1>\n<p>{} crashes, {} distinct signatures.</p>", rows.len(), count_by(rows, |row| row.signature.clone()).len());
+
+    html.push_str("<div class=\"charts\">\n");
+    bar_chart(&mut html, "By signature", &count_by(rows, |row| row.signature.clone()));
+    bar_chart(&mut html, "By build", &count_by(rows, |row| row.build.clone()));
+    bar_chart(&mut html, "By module", &count_by(rows, |row| row.module.clone()));
+    bar_chart(&mut html, "By scene", &count_by(rows, |row| row.scene.clone()));
+    bar_chart(&mut html, "By GPU vendor", &count_by(rows, |row| row.gpu_vendor.clone()));
+    bar_chart(&mut html, "By GPU", &count_by(rows, |row| row.gpu.clone()));
+    bar_chart(&mut html, "By RAM", &count_by(rows, |row| row.ram.clone()));
+    bar_chart(&mut html, "By uptime", &count_by(rows, |row| row.bucket.clone()));
+    bar_chart(&mut html, "By VR runtime", &count_by(rows, |row| row.vr_runtime.clone()));
+    bar_chart(&mut html, "By VR headset", &count_by(rows, |row| row.vr_headset.clone()));
+    //a crash can match several rules, or none
+    let diagnoses: Vec<&String> = rows.iter().flat_map(|row| &row.diagnoses).collect();
+    bar_chart(&mut html, "By diagnosis", &count_by(&diagnoses, |name| name.to_string()));
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Crashes</h2>\n<table class=\"sortable\">\n<tr><th>Time</th><th>Signature</th><th>Scene</th><th>Module</th><th>GPU</th><th>Uptime</th><th>Build</th></tr>\n");
+    for row in rows {
+        let _ = writeln!(html, "<tr><td data-sort=\"{}\">{}</td><td><a href=\"{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td data-sort=\"{}\">{} ({})</td><td class=\"mono\">{}</td></tr>",
+            row.timestamp, escape(&row.time), escape(&row.page), escape(&row.signature), escape(&row.scene), escape(&row.module),
+            escape(&row.gpu_vendor), row.uptime_ms, escape(&row.uptime), escape(&row.bucket), escape(&row.build));
+    }
+    html.push_str("</table>\n");
+
+    if !batch.failures.is_empty() {
+        html.push_str("<h2>Unreadable files</h2>\n<table class=\"sortable\">\n<tr><th>File</th><th>Error</th></tr>\n");
+        for (path, e) in &batch.failures {
+            let _ = writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", escape(&path.display().to_string()), escape(&e.to_string()));
+        }
+        html.push_str("</table>\n");
+    }
+
+    let _ = write!(html, "<script>{SORT_SCRIPT}</script>\n</body>\n</html>\n");
+    html
+}
+
+fn bar_chart(html: &mut String, title: &str, counts: &[(String, usize)]) {
+    let max = counts.iter().map(|(_, count)| *count).max().unwrap_or(1);
+    let _ = writeln!(html, "<div>\n<h3>{}</h3>\n<table class=\"bars\">", escape(title));
+    for (label, count) in counts {
+        let width = *count as f64 / max as f64 * 100.0;
+        let _ = writeln!(html, "<tr><td class=\"label\">{}</td><td><div class=\"bar\" style=\"width: {width:.1}%\"></div></td><td>{count}</td></tr>", escape(label));
+    }
+    html.push_str("</table>\n</div>\n");
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+use phf::phf_map;
+use crate::g2_crash_metrics::G2Exception;
+use crate::modules::{find_module, Module};
+use crate::status_codes::{CodeKind, DecodedCode};
+
+pub const STATUS_BREAKPOINT: u32 = 0x80000003;
+pub const STATUS_ACCESS_VIOLATION: u32 = 0xC0000005;
+pub const STATUS_IN_PAGE_ERROR: u32 = 0xC0000006;
+pub const STATUS_STACK_OVERFLOW: u32 = 0xC00000FD;
+pub const STATUS_STACK_BUFFER_OVERRUN: u32 = 0xC0000409;
+pub const STATUS_FAIL_FAST_EXCEPTION: u32 = 0xC0000602;
+pub const STATUS_CPP_EH_EXCEPTION: u32 = 0xE06D7363;
+
+static EXCEPTION_FLAGS: [(u32, &str); 8] = [
+    (0x01, "EXCEPTION_NONCONTINUABLE"),
+    (0x02, "EXCEPTION_UNWINDING"),
+    (0x04, "EXCEPTION_EXIT_UNWIND"),
+    (0x08, "EXCEPTION_STACK_INVALID"),
+    (0x10, "EXCEPTION_NESTED_CALL"),
+    (0x20, "EXCEPTION_TARGET_UNWIND"),
+    (0x40, "EXCEPTION_COLLIDED_UNWIND"),
+    (0x80, "EXCEPTION_SOFTWARE_ORIGINATE"),
+];
+
+static FAST_FAIL_CODES: phf::Map<u64, &'static str> = phf_map! {
+    0u64 => "FAST_FAIL_LEGACY_GS_VIOLATION",
+    1u64 => "FAST_FAIL_VTGUARD_CHECK_FAILURE",
+    2u64 => "FAST_FAIL_STACK_COOKIE_CHECK_FAILURE",
+    3u64 => "FAST_FAIL_CORRUPT_LIST_ENTRY",
+    4u64 => "FAST_FAIL_INCORRECT_STACK",
+    5u64 => "FAST_FAIL_INVALID_ARG",
+    6u64 => "FAST_FAIL_GS_COOKIE_INIT",
+    7u64 => "FAST_FAIL_FATAL_APP_EXIT",
+    8u64 => "FAST_FAIL_RANGE_CHECK_FAILURE",
+    9u64 => "FAST_FAIL_UNSAFE_REGISTRY_ACCESS",
+    10u64 => "FAST_FAIL_GUARD_ICALL_CHECK_FAILURE",
+    11u64 => "FAST_FAIL_GUARD_WRITE_CHECK_FAILURE",
+    12u64 => "FAST_FAIL_INVALID_FIBER_SWITCH",
+    13u64 => "FAST_FAIL_INVALID_SET_OF_CONTEXT",
+    14u64 => "FAST_FAIL_INVALID_REFERENCE_COUNT",
+    18u64 => "FAST_FAIL_INVALID_JUMP_BUFFER",
+    19u64 => "FAST_FAIL_MRDATA_MODIFIED",
+    20u64 => "FAST_FAIL_CERTIFICATION_FAILURE",
+    21u64 => "FAST_FAIL_INVALID_EXCEPTION_CHAIN",
+    22u64 => "FAST_FAIL_CRYPTO_LIBRARY",
+    23u64 => "FAST_FAIL_INVALID_CALL_IN_DLL_CALLOUT",
+    24u64 => "FAST_FAIL_INVALID_IMAGE_BASE",
+    25u64 => "FAST_FAIL_DLOAD_PROTECTION_FAILURE",
+    26u64 => "FAST_FAIL_UNSAFE_EXTENSION_CALL",
+    27u64 => "FAST_FAIL_DEPRECATED_SERVICE_INVOKED",
+    28u64 => "FAST_FAIL_INVALID_BUFFER_ACCESS",
+    29u64 => "FAST_FAIL_INVALID_BALANCED_TREE",
+    30u64 => "FAST_FAIL_INVALID_NEXT_THREAD",
+    31u64 => "FAST_FAIL_GUARD_ICALL_CHECK_SUPPRESSED",
+    32u64 => "FAST_FAIL_APCS_DISABLED",
+    33u64 => "FAST_FAIL_INVALID_IDLE_STATE",
+    34u64 => "FAST_FAIL_MRDATA_PROTECTION_FAILURE",
+    35u64 => "FAST_FAIL_UNEXPECTED_HEAP_EXCEPTION",
+    36u64 => "FAST_FAIL_INVALID_LOCK_STATE",
+    37u64 => "FAST_FAIL_GUARD_JUMPTABLE",
+    38u64 => "FAST_FAIL_INVALID_LONGJUMP_TARGET",
+    39u64 => "FAST_FAIL_INVALID_DISPATCH_CONTEXT",
+    40u64 => "FAST_FAIL_INVALID_THREAD",
+    41u64 => "FAST_FAIL_INVALID_SYSCALL_NUMBER",
+    42u64 => "FAST_FAIL_INVALID_FILE_OPERATION",
+    43u64 => "FAST_FAIL_LPAC_ACCESS_DENIED",
+    44u64 => "FAST_FAIL_GUARD_SS_FAILURE",
+    46u64 => "FAST_FAIL_INVALID_CONTROL_STACK",
+    47u64 => "FAST_FAIL_SET_CONTEXT_DENIED",
+    48u64 => "FAST_FAIL_INVALID_IAT",
+    49u64 => "FAST_FAIL_HEAP_METADATA_CORRUPTION",
+    50u64 => "FAST_FAIL_PAYLOAD_RESTRICTION_VIOLATION",
+    51u64 => "FAST_FAIL_LOW_LABEL_ACCESS_DENIED",
+    52u64 => "FAST_FAIL_ENCLAVE_CALL_FAILURE",
+    53u64 => "FAST_FAIL_UNHANDLED_LSS_EXCEPTON",
+    54u64 => "FAST_FAIL_ADMINLESS_ACCESS_DENIED",
+    55u64 => "FAST_FAIL_UNEXPECTED_CALL",
+    56u64 => "FAST_FAIL_CONTROL_INVALID_RETURN_ADDRESS",
+    57u64 => "FAST_FAIL_UNEXPECTED_HOST_BEHAVIOR",
+    58u64 => "FAST_FAIL_FLAGS_CORRUPTION",
+    59u64 => "FAST_FAIL_VEH_CORRUPTION",
+    60u64 => "FAST_FAIL_ETW_CORRUPTION",
+    61u64 => "FAST_FAIL_RIO_ABORT",
+    62u64 => "FAST_FAIL_INVALID_PFN",
+    0xFFFFFFFFu64 => "FAST_FAIL_INVALID_FAST_FAIL_CODE",
+};
+
+//the lowest 64KB of the address space is never mapped on Windows
+const NULL_REGION_END: u64 = 0x10000;
+
+//the msvc C++ exception magic stored in the first parameter
+const CPP_EH_MAGIC: u64 = 0x19930520;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessType {
+    Read,
+    Write,
+    Execute,
+    Other(u64),
+}
+
+impl From<u64> for AccessType {
+    fn from(value: u64) -> Self {
+        match value {
+            0 => AccessType::Read,
+            1 => AccessType::Write,
+            8 => AccessType::Execute,
+            other => AccessType::Other(other),
+        }
+    }
+}
+
+impl fmt::Display for AccessType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccessType::Read => write!(f, "read"),
+            AccessType::Write => write!(f, "write"),
+            AccessType::Execute => write!(f, "execute (DEP violation)"),
+            AccessType::Other(value) => write!(f, "unknown access type ({value})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressClass {
+    NullPage,
+    NearNull(u64),
+    NonCanonical,
+    Module { name: String, offset: u64 },
+    Unknown,
+}
+
+impl AddressClass {
+    pub fn classify(address: u64, modules: &[Module]) -> Self {
+        let upper_bits = address >> 47;
+        if address == 0 {
+            AddressClass::NullPage
+        } else if address < NULL_REGION_END {
+            AddressClass::NearNull(address)
+        } else if upper_bits != 0 && upper_bits != 0x1FFFF {
+            AddressClass::NonCanonical
+        } else if let Some((module, offset)) = find_module(modules, address) {
+            AddressClass::Module { name: module.name.clone(), offset }
+        } else {
+            AddressClass::Unknown
+        }
+    }
+}
+
+impl fmt::Display for AddressClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressClass::NullPage => write!(f, "null pointer"),
+            AddressClass::NearNull(offset) => write!(f, "near-null, likely a field at offset 0x{offset:x} of a null pointer"),
+            AddressClass::NonCanonical => write!(f, "non-canonical address, likely a corrupted pointer"),
+            AddressClass::Module { name, offset } => write!(f, "inside {name}+0x{offset:x}"),
+            AddressClass::Unknown => write!(f, "outside of any loaded module (heap, stack or unmapped)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExceptionDetails {
+    AccessViolation { access: AccessType, address: u64, class: AddressClass },
+    InPageError { access: AccessType, address: u64, class: AddressClass, status: u32 },
+    StackOverflow { access: AccessType, address: u64 },
+    FailFast { code: u64 },
+    CppException { throw_object: u64, throw_info: u64 },
+    Breakpoint,
+    None,
+}
+
+impl ExceptionDetails {
+    pub fn new(exception: &G2Exception, modules: &[Module]) -> Self {
+        let params = exception.exception_num_parameters;
+        let info = [
+            exception.exception_information_01,
+            exception.exception_information_02,
+            exception.exception_information_03,
+        ];
+
+        match exception.exception_code {
+            STATUS_ACCESS_VIOLATION if params >= 2 => ExceptionDetails::AccessViolation {
+                access: AccessType::from(info[0]),
+                address: info[1],
+                class: AddressClass::classify(info[1], modules),
+            },
+            STATUS_IN_PAGE_ERROR if params >= 3 => ExceptionDetails::InPageError {
+                access: AccessType::from(info[0]),
+                address: info[1],
+                class: AddressClass::classify(info[1], modules),
+                status: info[2] as u32,
+            },
+            STATUS_STACK_OVERFLOW if params >= 2 => ExceptionDetails::StackOverflow {
+                access: AccessType::from(info[0]),
+                address: info[1],
+            },
+            STATUS_STACK_BUFFER_OVERRUN | STATUS_FAIL_FAST_EXCEPTION if params >= 1 => ExceptionDetails::FailFast {
+                code: info[0],
+            },
+            STATUS_CPP_EH_EXCEPTION if params >= 3 && info[0] == CPP_EH_MAGIC => ExceptionDetails::CppException {
+                throw_object: info[1],
+                throw_info: info[2],
+            },
+            STATUS_BREAKPOINT => ExceptionDetails::Breakpoint,
+            _ => ExceptionDetails::None,
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        match self {
+            ExceptionDetails::AccessViolation { access, address, class } => vec![
+                format!("Access violation while trying to {access} 0x{address:x}"),
+                format!("Target: {class}"),
+            ],
+            ExceptionDetails::InPageError { access, address, class, status } => vec![
+                format!("In-page error while trying to {access} 0x{address:x}"),
+                format!("Target: {class}"),
+                format!("Underlying status: {}", DecodedCode::new(*status, CodeKind::NtStatus)),
+            ],
+            ExceptionDetails::StackOverflow { access, address } => vec![
+                format!("Stack overflow, {access} of the guard page at 0x{address:x}"),
+                "Likely caused by unbounded recursion or a very large stack allocation".to_string(),
+            ],
+            ExceptionDetails::FailFast { code } => vec![
+                format!("Fail fast code: {code} ({})", FAST_FAIL_CODES.get(code).unwrap_or(&"unknown")),
+                "The process terminated itself after detecting a corrupted state".to_string(),
+            ],
+            ExceptionDetails::CppException { throw_object, throw_info } => vec![
+                "Unhandled C++ exception".to_string(),
+                format!("Thrown object: 0x{throw_object:x}"),
+                format!("Throw info: 0x{throw_info:x}"),
+            ],
+            ExceptionDetails::Breakpoint => vec![
+                "Breakpoint hit, usually an assertion or a debug break left in the build".to_string(),
+            ],
+            ExceptionDetails::None => vec![],
+        }
+    }
+}
+
+pub fn decode_flags(flags: u32) -> Vec<&'static str> {
+    EXCEPTION_FLAGS.iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules() -> Vec<Module> {
+        vec![Module {
+            name: "HITMAN3.exe".to_string(),
+            base: 0x140000000,
+            size: 0x1000000,
+            pdb_guid: String::new(),
+            pdb_age: String::new(),
+        }]
+    }
+
+    #[test]
+    fn addresses_are_classified() {
+        let modules = modules();
+        assert_eq!(AddressClass::classify(0, &modules), AddressClass::NullPage);
+        assert_eq!(AddressClass::classify(0x18, &modules), AddressClass::NearNull(0x18));
+        assert_eq!(AddressClass::classify(0xFFFF, &modules), AddressClass::NearNull(0xFFFF));
+        assert_eq!(AddressClass::classify(0x0000_8000_0000_0000, &modules), AddressClass::NonCanonical);
+        assert_eq!(AddressClass::classify(0xDEAD_BEEF_DEAD_BEEF, &modules), AddressClass::NonCanonical);
+        assert_eq!(AddressClass::classify(0x140123456, &modules), AddressClass::Module { name: "HITMAN3.exe".to_string(), offset: 0x123456 });
+        assert_eq!(AddressClass::classify(0x141000000, &modules), AddressClass::Unknown);
+        //kernel half addresses are canonical
+        assert_eq!(AddressClass::classify(0xFFFF_8000_0000_0000, &modules), AddressClass::Unknown);
+    }
+
+    #[test]
+    fn access_violations_are_interpreted() {
+        let exception = G2Exception {
+            exception_code: STATUS_ACCESS_VIOLATION,
+            exception_num_parameters: 2,
+            exception_information_01: 1,
+            exception_information_02: 0x18,
+            ..Default::default()
+        };
+        assert_eq!(ExceptionDetails::new(&exception, &modules()), ExceptionDetails::AccessViolation {
+            access: AccessType::Write,
+            address: 0x18,
+            class: AddressClass::NearNull(0x18),
+        });
+        //without its parameters there is nothing to interpret
+        let exception = G2Exception { exception_num_parameters: 0, ..exception };
+        assert_eq!(ExceptionDetails::new(&exception, &modules()), ExceptionDetails::None);
+    }
+
+    #[test]
+    fn flags_are_decoded() {
+        assert!(decode_flags(0).is_empty());
+        assert_eq!(decode_flags(EXCEPTION_FLAGS[0].0), vec![EXCEPTION_FLAGS[0].1]);
+    }
+}
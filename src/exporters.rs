@@ -0,0 +1,227 @@
+use std::path::Path;
+use anyhow::{anyhow, Error};
+use serde::Serialize;
+use serde_json::Value;
+use crate::crash_export::CrashExport;
+use crate::html_report;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    PrettyJson,
+    Yaml,
+    Toml,
+    Cbor,
+    MessagePack,
+    Csv,
+    Html,
+}
+
+pub const EXPORT_FORMATS: [ExportFormat; 8] = [
+    ExportFormat::Json,
+    ExportFormat::PrettyJson,
+    ExportFormat::Yaml,
+    ExportFormat::Toml,
+    ExportFormat::Cbor,
+    ExportFormat::MessagePack,
+    ExportFormat::Csv,
+    ExportFormat::Html,
+];
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        match name.to_lowercase().as_str() {
+            "json" | "compact" => Some(ExportFormat::Json),
+            "pretty" | "pretty-json" => Some(ExportFormat::PrettyJson),
+            "yaml" | "yml" => Some(ExportFormat::Yaml),
+            "toml" => Some(ExportFormat::Toml),
+            "cbor" => Some(ExportFormat::Cbor),
+            "msgpack" | "messagepack" | "mpk" => Some(ExportFormat::MessagePack),
+            "csv" => Some(ExportFormat::Csv),
+            "html" | "htm" => Some(ExportFormat::Html),
+            _ => None,
+        }
+    }
+
+    //.json is ambiguous, it maps to compact json like the original save action
+    pub fn from_path(path: &Path) -> Option<ExportFormat> {
+        path.extension().and_then(|extension| extension.to_str()).and_then(Self::from_name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::PrettyJson => "pretty JSON",
+            ExportFormat::Yaml => "YAML",
+            ExportFormat::Toml => "TOML",
+            ExportFormat::Cbor => "CBOR",
+            ExportFormat::MessagePack => "MessagePack",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Html => "HTML report",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json | ExportFormat::PrettyJson => "json",
+            ExportFormat::Yaml => "yaml",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Cbor => "cbor",
+            ExportFormat::MessagePack => "msgpack",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    pub fn next(self) -> ExportFormat {
+        let index = EXPORT_FORMATS.iter().position(|format| *format == self).unwrap_or(0);
+        EXPORT_FORMATS[(index + 1) % EXPORT_FORMATS.len()]
+    }
+
+    //a single crash is written as a document of its own, several crashes as a list
+    pub fn write(self, exports: &[CrashExport]) -> Result<Vec<u8>, Error> {
+        match exports {
+            [] => Err(anyhow!("nothing to export")),
+            exports if self == ExportFormat::Html => Ok(html_report::render(exports).into_bytes()),
+            [export] if self != ExportFormat::Csv => self.write_document(export),
+            exports => match self {
+                //toml documents have to be a table
+                ExportFormat::Toml => self.write_document(&CrashList { crashes: exports }),
+                _ => self.write_document(&exports),
+            },
+        }
+    }
+
+    fn write_document<T: Serialize>(self, document: &T) -> Result<Vec<u8>, Error> {
+        Ok(match self {
+            ExportFormat::Json => serde_json::to_vec(document)?,
+            ExportFormat::PrettyJson => serde_json::to_vec_pretty(document)?,
+            ExportFormat::Yaml => serde_yaml::to_string(document)?.into_bytes(),
+            ExportFormat::Toml => toml::to_string_pretty(&toml_value(serde_json::to_value(document)?))?.into_bytes(),
+            ExportFormat::Cbor => {
+                let mut bytes = vec![];
+                ciborium::ser::into_writer(document, &mut bytes)?;
+                bytes
+            }
+            ExportFormat::MessagePack => rmp_serde::to_vec_named(document)?,
+            ExportFormat::Csv => write_csv(&serde_json::to_value(document)?)?,
+            ExportFormat::Html => return Err(anyhow!("html reports are rendered from the crash data, not serialized")),
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct CrashList<'a, 'b> {
+    crashes: &'b [CrashExport<'a>],
+}
+
+//toml integers are i64, larger u64 addresses and parameters are written as hex strings instead.
+//toml has no null either, so empty fields are left out
+fn toml_value(value: Value) -> Value {
+    match value {
+        Value::Number(number) => match number.as_u64() {
+            Some(number) if number > i64::MAX as u64 => Value::String(format!("{number:#X}")),
+            _ => Value::Number(number),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(toml_value).collect()),
+        Value::Object(fields) => Value::Object(fields.into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(name, value)| (name, toml_value(value)))
+            .collect()),
+        value => value,
+    }
+}
+
+//one row per crash, nested fields become dotted columns
+fn write_csv(document: &Value) -> Result<Vec<u8>, Error> {
+    let documents = match document {
+        Value::Array(documents) => documents.iter().collect(),
+        document => vec![document],
+    };
+
+    let rows: Vec<Vec<(String, String)>> = documents.into_iter()
+        .map(|document| {
+            let mut row = vec![];
+            flatten("", document, &mut row);
+            row
+        })
+        .collect();
+
+    let mut columns: Vec<&str> = vec![];
+    for (column, _) in rows.iter().flatten() {
+        if !columns.contains(&column.as_str()) {
+            columns.push(column);
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&columns)?;
+    for row in &rows {
+        writer.write_record(columns.iter().map(|column| {
+            row.iter().find(|(key, _)| key == column).map(|(_, value)| value.as_str()).unwrap_or("")
+        }))?;
+    }
+    Ok(writer.into_inner()?)
+}
+
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+    let key = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{prefix}.{name}") };
+    match value {
+        Value::Object(fields) => fields.iter().for_each(|(name, value)| flatten(&key(name), value, row)),
+        //lists of values share one column, lists of records stay json to keep the column count stable
+        Value::Array(items) if items.iter().all(|item| !item.is_object() && !item.is_array()) => {
+            let values: Vec<String> = items.iter().map(|item| match item {
+                Value::String(text) => text.clone(),
+                item => item.to_string(),
+            }).collect();
+            row.push((prefix.to_string(), values.join("; ")));
+        }
+        Value::Array(_) => row.push((prefix.to_string(), value.to_string())),
+        Value::Null => row.push((prefix.to_string(), String::new())),
+        Value::String(text) => row.push((prefix.to_string(), text.clone())),
+        value => row.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crash_export::import_metrics;
+    use binrw::NullString;
+    use crate::g2_crash_metrics::{G2CrashMetrics, NullStringS};
+    use crate::scenes::SceneTable;
+
+    #[test]
+    fn toml_round_trips_u64_max() {
+        let mut metrics = G2CrashMetrics::default();
+        metrics.exception.exception_address = u64::MAX;
+        metrics.exception.exception_information_01 = u64::MAX;
+        metrics.exception.exception_information_02 = 0x1234;
+        //a non-canonical frame address ends up in the derived data
+        metrics.callstack = NullStringS::from_str("FFFFFFFFFFFFFFFF").unwrap();
+        let export = CrashExport::new(&metrics, &SceneTable::load_default(), None);
+
+        let bytes = ExportFormat::Toml.write(&[export]).unwrap();
+        let imported = import_metrics(&bytes, ExportFormat::Toml).unwrap();
+        assert_eq!(imported.exception.exception_address, u64::MAX);
+        assert_eq!(imported.exception.exception_information_01, u64::MAX);
+        assert_eq!(imported.exception.exception_information_02, 0x1234);
+        assert_eq!(imported.callstack.to_string(), "FFFFFFFFFFFFFFFF");
+    }
+
+    #[test]
+    fn non_utf8_strings_round_trip_in_every_format() {
+        let scene = b"assembly:/_pro/scenes/\xff\xfe/_scene.entity".to_vec();
+        let metrics = G2CrashMetrics {
+            scene: NullStringS { string: NullString(scene.clone()) },
+            ..Default::default()
+        };
+        let scenes = SceneTable::load_default();
+
+        for format in [ExportFormat::Json, ExportFormat::PrettyJson, ExportFormat::Yaml, ExportFormat::Toml, ExportFormat::Cbor, ExportFormat::MessagePack] {
+            let bytes = format.write(&[CrashExport::new(&metrics, &scenes, None)]).unwrap_or_else(|e| panic!("{} export failed: {e}", format.name()));
+            let imported = import_metrics(&bytes, format).unwrap_or_else(|e| panic!("{} import failed: {e}", format.name()));
+            assert_eq!(imported.scene.string.0, scene, "{}", format.name());
+        }
+    }
+}
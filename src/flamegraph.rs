@@ -0,0 +1,183 @@
+use std::fmt::Write;
+use md5::{Digest, Md5};
+use crate::batch::count;
+use crate::callstack::Frame;
+use crate::html_report::escape;
+
+const SVG_WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: f64 = 16.0;
+const MARGIN: f64 = 10.0;
+const TITLE_HEIGHT: f64 = 40.0;
+//roughly the width of one character of the 12px label font
+const CHAR_WIDTH: f64 = 7.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlamegraphFormat {
+    Folded,
+    Svg,
+}
+
+impl FlamegraphFormat {
+    pub fn from_name(name: &str) -> Option<FlamegraphFormat> {
+        match name.to_lowercase().as_str() {
+            "folded" | "txt" | "collapsed" => Some(FlamegraphFormat::Folded),
+            "svg" => Some(FlamegraphFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+//root first, the crashing frame last. modules only collapses runs of frames in the same module
+pub fn stack_names(frames: &[Frame], modules_only: bool) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for frame in frames.iter().rev() {
+        let name = match (&frame.module, modules_only) {
+            (Some(module), true) => module.clone(),
+            _ => frame.location(),
+        };
+        if !(modules_only && names.last() == Some(&name)) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+//identical stacks are counted once, in the order of the folded text
+pub fn fold(stacks: &[Vec<String>]) -> Vec<(String, usize)> {
+    //';' separates frames in the folded format
+    let lines = stacks.iter()
+        .filter(|stack| !stack.is_empty())
+        .map(|stack| stack.iter().map(|name| name.replace(';', ":")).collect::<Vec<_>>().join(";"));
+    let mut folded = count(lines);
+    folded.sort();
+    folded
+}
+
+pub fn folded_text(folded: &[(String, usize)]) -> String {
+    folded.iter().map(|(stack, count)| format!("{stack} {count}\n")).collect()
+}
+
+struct Node {
+    name: String,
+    count: usize,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn insert(&mut self, names: &[String], count: usize) {
+        self.count += count;
+        if let Some((name, rest)) = names.split_first() {
+            let index = match self.children.iter().position(|child| child.name == *name) {
+                Some(index) => index,
+                None => {
+                    self.children.push(Node { name: name.clone(), count: 0, children: vec![] });
+                    self.children.len() - 1
+                }
+            };
+            self.children[index].insert(rest, count);
+        }
+    }
+
+    fn depth(&self) -> usize {
+        1 + self.children.iter().map(Node::depth).max().unwrap_or(0)
+    }
+}
+
+//a static flamegraph, the root sits at the bottom and the crashing frames on top
+pub fn render_svg(folded: &[(String, usize)], title: &str) -> String {
+    let mut root = Node { name: "all".to_string(), count: 0, children: vec![] };
+    for (stack, count) in folded {
+        let names: Vec<String> = stack.split(';').map(str::to_string).collect();
+        root.insert(&names, *count);
+    }
+
+    let depth = root.depth();
+    let height = TITLE_HEIGHT + depth as f64 * FRAME_HEIGHT + MARGIN * 2.0;
+    let mut svg = String::new();
+    let _ = writeln!(svg, "<?xml version=\"1.0\" standalone=\"no\"?>");
+    let _ = writeln!(svg, "<svg version=\"1.1\" width=\"{SVG_WIDTH}\" height=\"{height}\" viewBox=\"0 0 {SVG_WIDTH} {height}\" xmlns=\"http://www.w3.org/2000/svg\">");
+    let _ = writeln!(svg, "<style>text {{ font-family: Consolas, \"Courier New\", monospace; font-size: 12px; }} rect {{ stroke: #fff; stroke-width: .5; }}</style>");
+    let _ = writeln!(svg, "<rect x=\"0\" y=\"0\" width=\"{SVG_WIDTH}\" height=\"{height}\" fill=\"#fafafa\"/>");
+    let _ = writeln!(svg, "<text x=\"{}\" y=\"24\" text-anchor=\"middle\" style=\"font-size: 17px\">{}</text>", SVG_WIDTH / 2.0, escape(title));
+
+    if root.count > 0 {
+        let scale = (SVG_WIDTH - MARGIN * 2.0) / root.count as f64;
+        let bottom = height - MARGIN - FRAME_HEIGHT;
+        draw_node(&mut svg, &root, root.count, MARGIN, bottom, scale);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn draw_node(svg: &mut String, node: &Node, total: usize, x: f64, y: f64, scale: f64) {
+    let width = node.count as f64 * scale;
+    let share = node.count as f64 / total as f64 * 100.0;
+    let crashes = if node.count == 1 { "crash" } else { "crashes" };
+    let tooltip = format!("{} ({} {crashes}, {share:.1}%)", node.name, node.count);
+    let _ = writeln!(svg, "<g><title>{}</title><rect x=\"{x:.2}\" y=\"{y:.2}\" width=\"{width:.2}\" height=\"{FRAME_HEIGHT}\" fill=\"{}\"/>", escape(&tooltip), color(&node.name));
+    let chars = ((width - 6.0) / CHAR_WIDTH) as usize;
+    if chars >= 3 {
+        let label: String = if node.name.chars().count() > chars {
+            node.name.chars().take(chars - 2).chain("..".chars()).collect()
+        } else {
+            node.name.clone()
+        };
+        let _ = writeln!(svg, "<text x=\"{:.2}\" y=\"{:.2}\">{}</text>", x + 3.0, y + FRAME_HEIGHT - 4.0, escape(&label));
+    }
+    svg.push_str("</g>\n");
+
+    let mut child_x = x;
+    for child in &node.children {
+        draw_node(svg, child, total, child_x, y - FRAME_HEIGHT, scale);
+        child_x += child.count as f64 * scale;
+    }
+}
+
+//frames of the same module share a hue so subsystems stand out
+fn color(name: &str) -> String {
+    let module = name.split('+').next().unwrap_or(name);
+    let digest = Md5::digest(module.to_lowercase().as_bytes());
+    let red = 200 + digest[0] as u32 % 55;
+    let green = 60 + digest[1] as u32 % 140;
+    let blue = digest[2] as u32 % 60;
+    format!("rgb({red},{green},{blue})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(module: &str, offset: u64) -> Frame {
+        Frame { address: 0x140000000 + offset, module: Some(module.to_string()), offset: Some(offset) }
+    }
+
+    #[test]
+    fn stacks_are_named_root_first() {
+        let frames = vec![
+            frame("HITMAN3.exe", 0x10),
+            frame("HITMAN3.exe", 0x20),
+            Frame { address: 0x1234, module: None, offset: None },
+            frame("ntdll.dll", 0x30),
+        ];
+        assert_eq!(stack_names(&frames, false), vec!["ntdll.dll+0x30", "0x1234", "HITMAN3.exe+0x20", "HITMAN3.exe+0x10"]);
+        assert_eq!(stack_names(&frames, true), vec!["ntdll.dll", "0x1234", "HITMAN3.exe"]);
+    }
+
+    #[test]
+    fn identical_stacks_are_folded() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let stacks = vec![names(&["main", "b"]), names(&["main", "a"]), vec![], names(&["main", "b"]), names(&["main", "x;y"])];
+        let folded = fold(&stacks);
+        assert_eq!(folded, vec![("main;a".to_string(), 1), ("main;b".to_string(), 2), ("main;x:y".to_string(), 1)]);
+        assert_eq!(folded_text(&folded), "main;a 1\nmain;b 2\nmain;x:y 1\n");
+    }
+
+    #[test]
+    fn svg_has_a_rect_per_frame() {
+        let folded = vec![("main;a".to_string(), 1), ("main;b".to_string(), 2)];
+        let svg = render_svg(&folded, "crashes");
+        //background, all, main, a and b
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert!(svg.contains("<title>b (2 crashes, 66.7%)</title>"));
+    }
+}
@@ -5,51 +5,55 @@ use std::io::Read;
 
 use std::io::Cursor;
 use anyhow::{anyhow, Error};
-use binrw::{BinRead, NullString, BinReaderExt};
+use binrw::{BinRead, BinWrite, NullString, BinReaderExt, BinWriterExt};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{Schema, SchemaObject};
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de;
 use serde::de::Visitor;
 
-#[derive(BinRead, Debug, Default, Serialize, Deserialize)]
-#[br(magic = b"MC2G")]
+#[derive(BinRead, BinWrite, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[brw(magic = b"MC2G")]
 pub struct G2CrashMetrics {
     pub version: u32,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub uptimems: u64,
 
-    #[br(pad_size_to(0x100))]
+    #[brw(pad_size_to(0x100))]
     pub scene: NullStringS,
 
-    #[br(pad_size_to(0x20))]
+    #[brw(pad_size_to(0x20))]
     pub net_role: NullStringS,
 
-    #[br(pad_size_to(0x20))]
+    #[brw(pad_size_to(0x20))]
     pub online_server_version: NullStringS,
 
-    #[br(pad_size_to(0x200))]
+    #[brw(pad_size_to(0x200))]
     pub system_info: NullStringS,
 
-    #[br(pad_size_to(0x400))]
+    #[brw(pad_size_to(0x400))]
     pub settings_info: NullStringS,
 
-    #[br(pad_size_to(0x4000))]
+    #[brw(pad_size_to(0x4000))]
     pub gpu_crash_report: NullStringS,
 
-    #[br(pad_size_to(0x80))]
+    #[brw(pad_size_to(0x80))]
     pub vr_data: NullStringS,
 
-    #[br(pad_size_to(0x200))]
+    #[brw(pad_size_to(0x200))]
     pub vr_hdm_description: NullStringS,
 
-    #[br(pad_size_to(0x80))]
+    #[brw(pad_size_to(0x80))]
     pub operating_system: NullStringS,
 
-    #[br(pad_size_to(0x4000))]
+    #[brw(pad_size_to(0x4000))]
     pub modules: NullStringS,
 
-    #[br(pad_size_to(0x800))]
+    #[brw(pad_size_to(0x800))]
     pub callstack: NullStringS,
 
-    #[br(pad_size_to(0x100))]
+    #[brw(pad_size_to(0x100))]
     pub camera: NullStringS,
 
     pub exception: G2Exception,
@@ -57,19 +61,24 @@ pub struct G2CrashMetrics {
     pub unknown: [u8; 0x8],
 }
 
-#[derive(BinRead, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[derive(BinRead, BinWrite, Debug, Default, Copy, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct G2Exception {
     pub exception_code: u32,
     pub exception_flags: u32,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub exception_address: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub exception_num_parameters: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub exception_information_01: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub exception_information_02: u64,
+    #[serde(deserialize_with = "deserialize_u64")]
     pub exception_information_03: u64,
 }
 
 pub const CRASH_METRICS_SIZE: usize = 0x9388;
-pub const RAND_SEQUENCE: [u8; CRASH_METRICS_SIZE] = {
+pub static RAND_SEQUENCE: [u8; CRASH_METRICS_SIZE] = {
 
     let mut array: [u8; CRASH_METRICS_SIZE] = [0; CRASH_METRICS_SIZE];
     let mut seed: i32 = 0;
@@ -100,25 +109,44 @@ impl G2CrashMetrics {
         Ok(data)
     }
 
+    fn encipher(data: Vec<u8>) -> Vec<u8> {
+        RAND_SEQUENCE.iter().zip(data).map(|(seq, byte)| seq.wrapping_sub(byte)).collect()
+    }
+
     pub fn new(path: &str) -> Result<Self, Error> {
         if let Ok(bytes) = get_file_as_byte_vec(path){
+            Self::from_bytes(bytes)
+        } else {
+            Err(anyhow!("Failed to find the given g2cm file"))
+        }
+    }
 
-            if let Ok(deciphered_data) = Self::decipher(bytes) {
+    //parses the encrypted contents of a crash_metrics.dat
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        if let Ok(deciphered_data) = Self::decipher(bytes) {
 
-                let mut reader = Cursor::new(deciphered_data);
-                Ok(reader.read_ne().unwrap())
+            let mut reader = Cursor::new(deciphered_data);
+            Ok(reader.read_ne()?)
 
-            } else {
-                Err(anyhow!("Failed to parse given g2cm file"))
-            }
         } else {
-            Err(anyhow!("Failed to find the given g2cm file"))
+            Err(anyhow!("Failed to parse given g2cm file"))
+        }
+    }
+
+    //encrypts the metrics into the layout of a crash_metrics.dat, with zeroed padding
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = Cursor::new(vec![]);
+        writer.write_ne(self)?;
+        let data = writer.into_inner();
+        if data.len() != CRASH_METRICS_SIZE {
+            return Err(anyhow!("the crash has fields too long for the crash_metrics.dat layout"));
         }
+        Ok(Self::encipher(data))
     }
 }
 
 //wrapper struct to implement Serialize
-#[derive(BinRead, Debug, Default)]
+#[derive(BinRead, BinWrite, Debug, Default)]
 pub struct NullStringS {
     pub string : NullString,
 }
@@ -137,9 +165,27 @@ impl NullStringS {
     }
 }
 
+//strings that aren't valid utf-8 are written as byte arrays so they survive a round-trip
 impl Serialize for NullStringS {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        serializer.serialize_str(&self.string.to_string())
+        match std::str::from_utf8(&self.string) {
+            Ok(string) => serializer.serialize_str(string),
+            //a sequence rather than serialize_bytes, which serde_yaml doesn't support
+            Err(_) => serializer.collect_seq(self.string.iter()),
+        }
+    }
+}
+
+impl JsonSchema for NullStringS {
+    fn schema_name() -> String {
+        "NullString".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject::default();
+        schema.subschemas().any_of = Some(vec![gen.subschema_for::<String>(), gen.subschema_for::<Vec<u8>>()]);
+        schema.metadata().description = Some("a string, or its raw bytes when it isn't valid utf-8".to_string());
+        Schema::Object(schema)
     }
 }
 
@@ -148,7 +194,7 @@ impl<'de> Deserialize<'de> for NullStringS {
         where
             D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(NullStringS { string: NullString::default()})
+        deserializer.deserialize_any(NullStringS { string: NullString::default()})
     }
 }
 
@@ -156,7 +202,25 @@ impl<'de> Visitor<'de> for NullStringS {
     type Value = NullStringS;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a string")
+        formatter.write_str("a string or a byte array")
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+    {
+        Ok(NullStringS { string: NullString(value.to_vec()) })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+    {
+        let mut bytes = vec![];
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Ok(NullStringS { string: NullString(bytes) })
     }
 
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -167,6 +231,34 @@ impl<'de> Visitor<'de> for NullStringS {
     }
 }
 
+//toml exports write values above i64::MAX as hex strings
+fn deserialize_u64<'de, D>(deserializer: D) -> Result<u64, D::Error> where D: Deserializer<'de> {
+    struct U64Visitor;
+
+    impl<'de> Visitor<'de> for U64Visitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an unsigned integer or a hex string")
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<u64, E> where E: de::Error {
+            Ok(value)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<u64, E> where E: de::Error {
+            u64::try_from(value).map_err(|_| E::custom(format!("{value} is negative")))
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<u64, E> where E: de::Error {
+            let digits = value.trim_start_matches("0x").trim_start_matches("0X");
+            u64::from_str_radix(digits, 16).map_err(|_| E::custom(format!("{value} is not a hex number")))
+        }
+    }
+
+    deserializer.deserialize_any(U64Visitor)
+}
+
 pub fn get_file_as_byte_vec(filename: &str) -> Result<Vec<u8>, Error> {
     if let Ok(mut f) = File::open(filename) {
         if let Ok(metadata) = fs::metadata(filename){
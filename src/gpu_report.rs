@@ -0,0 +1,206 @@
+use crate::status_codes::{search_name, CodeKind, DecodedCode};
+
+#[derive(Debug, Default, Clone)]
+pub struct DeviceRemoved {
+    pub reason: Option<DecodedCode>,
+    pub lines: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BreadcrumbList {
+    pub name: String,
+    pub ops: Vec<String>,
+    //number of completed operations, as reported by DRED or Aftermath
+    pub completed: Option<usize>,
+}
+
+impl BreadcrumbList {
+    pub fn last_completed(&self) -> Option<usize> {
+        self.completed.filter(|completed| *completed > 0).map(|completed| completed - 1)
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PageFault {
+    pub address: Option<u64>,
+    pub allocations: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GpuCrashReport {
+    pub device_removed: DeviceRemoved,
+    pub adapter: Vec<(String, String)>,
+    pub breadcrumbs: Vec<BreadcrumbList>,
+    pub page_faults: Vec<PageFault>,
+    pub unrecognized: Vec<String>,
+}
+
+#[derive(PartialEq)]
+enum Section {
+    None,
+    DeviceRemoved,
+    Adapter,
+    Breadcrumbs,
+    PageFault,
+}
+
+const ADAPTER_KEYS: [&str; 9] = ["adapter", "driver", "vendor", "device id", "deviceid", "gpu", "vram", "video memory", "description"];
+const DEVICE_REMOVED_KEYS: [&str; 4] = ["removed", "reason", "hresult", "tdr"];
+
+impl GpuCrashReport {
+    pub fn parse(report: &str) -> GpuCrashReport {
+        let mut parsed = GpuCrashReport::default();
+        let mut section = Section::None;
+
+        for line in report.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let lower = line.to_lowercase();
+
+            if let Some(header) = section_header(line) {
+                let header = header.to_lowercase();
+                section = if header.contains("breadcrumb") {
+                    parsed.breadcrumbs.push(BreadcrumbList { name: header_name(line), ..Default::default() });
+                    Section::Breadcrumbs
+                } else if header.contains("page fault") || header.contains("pagefault") {
+                    parsed.page_faults.push(PageFault::default());
+                    Section::PageFault
+                } else if DEVICE_REMOVED_KEYS.iter().any(|key| header.contains(key)) {
+                    Section::DeviceRemoved
+                } else if ADAPTER_KEYS.iter().any(|key| header.contains(key)) {
+                    Section::Adapter
+                } else {
+                    parsed.unrecognized.push(line.to_string());
+                    Section::None
+                };
+                continue;
+            }
+
+            let key_value = line.split_once(':')
+                .or_else(|| line.split_once('='))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()));
+
+            //breadcrumb operations can show up outside of a breadcrumb section
+            if lower.contains("_breadcrumb_op_") && section != Section::Breadcrumbs {
+                parsed.breadcrumbs.push(BreadcrumbList { name: "Breadcrumbs".to_string(), ..Default::default() });
+                section = Section::Breadcrumbs;
+            }
+
+            match (&section, key_value) {
+                (Section::Breadcrumbs, Some((key, value))) if is_completed_key(&key) => {
+                    if let Some(list) = parsed.breadcrumbs.last_mut() {
+                        list.completed = parse_number(&value).map(|n| n as usize);
+                    }
+                }
+                (Section::Breadcrumbs, Some((key, value))) if key.to_lowercase().contains("command list") || key.to_lowercase().contains("commandlist") || key.to_lowercase().contains("queue") => {
+                    let needs_new = parsed.breadcrumbs.last().map(|list| !list.ops.is_empty()).unwrap_or(true);
+                    if needs_new {
+                        parsed.breadcrumbs.push(BreadcrumbList::default());
+                    }
+                    if let Some(list) = parsed.breadcrumbs.last_mut() {
+                        list.name = format!("{key}: {value}");
+                    }
+                }
+                (Section::Breadcrumbs, _) => {
+                    if let Some(list) = parsed.breadcrumbs.last_mut() {
+                        list.ops.push(strip_index(line));
+                    }
+                }
+                (Section::PageFault, Some((key, value))) if is_address_key(&key) && parse_number(&value).is_some() => {
+                    if let Some(fault) = parsed.page_faults.last_mut() {
+                        fault.address = parse_number(&value);
+                    }
+                }
+                (Section::PageFault, _) => {
+                    if let Some(fault) = parsed.page_faults.last_mut() {
+                        fault.allocations.push(line.to_string());
+                    }
+                }
+                (_, Some((_, value))) if lower.contains("page fault") || lower.contains("pagefault") => {
+                    parsed.page_faults.push(PageFault { address: parse_number(&value), allocations: vec![] });
+                }
+                (_, Some((key, value))) if section == Section::DeviceRemoved || DEVICE_REMOVED_KEYS.iter().any(|k| key.to_lowercase().contains(k)) => {
+                    if parsed.device_removed.reason.is_none() {
+                        parsed.device_removed.reason = parse_reason(&value);
+                    }
+                    parsed.device_removed.lines.push((key, value));
+                }
+                (_, Some((key, value))) if section == Section::Adapter || ADAPTER_KEYS.iter().any(|k| key.to_lowercase().contains(k)) => {
+                    parsed.adapter.push((key, value));
+                }
+                _ => {
+                    if parsed.device_removed.reason.is_none() && (lower.contains("dxgi_error_") || lower.contains("device removed")) {
+                        parsed.device_removed.reason = parse_reason(line);
+                        parsed.device_removed.lines.push(("".to_string(), line.to_string()));
+                    } else {
+                        parsed.unrecognized.push(line.to_string());
+                    }
+                }
+            }
+        }
+        parsed
+    }
+}
+
+fn section_header(line: &str) -> Option<&str> {
+    if line.starts_with('[') && line.ends_with(']') && line.len() > 2 {
+        return Some(&line[1..line.len() - 1]);
+    }
+    if line.starts_with("==") || line.starts_with("--") {
+        let header = line.trim_matches(|c| c == '=' || c == '-' || c == ' ');
+        return if header.is_empty() { None } else { Some(header) };
+    }
+    if let Some(header) = line.strip_suffix(':') {
+        return if header.contains(':') { None } else { Some(header) };
+    }
+    None
+}
+
+fn header_name(line: &str) -> String {
+    line.trim_matches(|c| c == '[' || c == ']' || c == '=' || c == '-' || c == ':' || c == ' ').to_string()
+}
+
+fn is_completed_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace(' ', "");
+    key.contains("lastcompleted") || key.contains("completedop") || key == "completed" || key.contains("lastbreadcrumbvalue")
+}
+
+//"va", "faulting va", "pagefault va" or any key ending in "address", but not "value" or "available"
+fn is_address_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.split_whitespace().last() == Some("va") || key.ends_with("address")
+}
+
+fn strip_index(line: &str) -> String {
+    let without_brackets = match line.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map(|(_, op)| op).unwrap_or(line),
+        None => match line.split_once([':', '.', ')']) {
+            Some((index, op)) if !index.trim().is_empty() && index.trim().chars().all(|c| c.is_ascii_digit()) => op,
+            _ => line,
+        },
+    };
+    without_brackets.trim().to_string()
+}
+
+pub fn parse_number(value: &str) -> Option<u64> {
+    let token = value.split_whitespace().next()?;
+    match token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => token.parse::<u64>().ok(),
+    }
+}
+
+//finds an HRESULT in the value, either as a hex literal or as a symbolic name
+fn parse_reason(value: &str) -> Option<DecodedCode> {
+    for token in value.split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',') {
+        if let Some(hex) = token.strip_prefix("0x").or_else(|| token.strip_prefix("0X")) {
+            if let Ok(code) = u32::from_str_radix(hex, 16) {
+                return Some(DecodedCode::new(code, CodeKind::HResult));
+            }
+        }
+        if token.starts_with("DXGI_ERROR_") || token.starts_with("E_") {
+            if let Some(known) = search_name(token).first() {
+                return Some(DecodedCode::new(known.code, known.kind));
+            }
+        }
+    }
+    None
+}
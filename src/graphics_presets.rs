@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use crate::graphics_settings::{GraphicsSettings, Setting, SettingValue};
+
+static BUNDLED_PRESETS: &str = include_str!("../assets/graphics_presets.json");
+
+#[derive(Deserialize)]
+struct PresetFile {
+    name: String,
+    settings: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphicsPreset {
+    pub name: String,
+    pub settings: GraphicsSettings,
+}
+
+#[derive(Debug, Clone)]
+pub struct Deviation {
+    pub key: String,
+    pub actual: SettingValue,
+    pub expected: SettingValue,
+}
+
+impl Deviation {
+    pub fn describe(&self, preset_name: &str) -> String {
+        match (self.actual.as_bool(), self.expected.as_bool()) {
+            (Some(true), Some(false)) => format!("{} enabled while {preset_name} says off", self.key),
+            (Some(false), Some(true)) => format!("{} disabled while {preset_name} says on", self.key),
+            _ => format!("{} is {} while {preset_name} says {}", self.key, self.actual, self.expected),
+        }
+    }
+}
+
+impl GraphicsPreset {
+    pub fn new(name: String, settings: GraphicsSettings) -> GraphicsPreset {
+        GraphicsPreset { name, settings }
+    }
+
+    pub fn bundled() -> Vec<GraphicsPreset> {
+        let presets: Vec<PresetFile> = serde_json::from_str(BUNDLED_PRESETS).unwrap_or_default();
+        presets.into_iter()
+            .map(|preset| GraphicsPreset {
+                name: preset.name,
+                settings: GraphicsSettings {
+                    entries: preset.settings.into_iter()
+                        .map(|(key, value)| Setting { key, value: SettingValue::parse(&value) })
+                        .collect(),
+                },
+            })
+            .collect()
+    }
+
+    //settings the preset does not know about are not considered deviations
+    pub fn deviation(&self, setting: &Setting) -> Option<Deviation> {
+        let expected = self.settings.get(&setting.key)?;
+        if expected.matches(&setting.value) {
+            return None;
+        }
+        Some(Deviation {
+            key: setting.key.clone(),
+            actual: setting.value.clone(),
+            expected: expected.clone(),
+        })
+    }
+
+    pub fn shares_keys(&self, settings: &GraphicsSettings) -> bool {
+        settings.entries.iter().any(|setting| self.settings.get(&setting.key).is_some())
+    }
+
+    pub fn deviations(&self, settings: &GraphicsSettings) -> Vec<Deviation> {
+        settings.entries.iter().filter_map(|setting| self.deviation(setting)).collect()
+    }
+}
+
+//the bundled preset with the fewest deviations, ties go to the higher preset
+//presets sharing no setting with the crash have nothing to compare and are skipped
+pub fn closest_preset<'a>(presets: &'a [GraphicsPreset], settings: &GraphicsSettings) -> Option<(&'a GraphicsPreset, usize)> {
+    presets.iter()
+        .filter(|preset| preset.shares_keys(settings))
+        .map(|preset| (preset, preset.deviations(settings).len()))
+        .rev()
+        .min_by_key(|(_, deviations)| *deviations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closest_preset_has_the_fewest_deviations() {
+        let presets = GraphicsPreset::bundled();
+        let settings = GraphicsSettings::parse("Texture Quality: Medium\nSSAO: true\nShadow Quality: Medium\nMotion Blur: false");
+        let (preset, deviations) = closest_preset(&presets, &settings).unwrap();
+        assert_eq!(preset.name, "Medium");
+        assert_eq!(deviations, 1);
+    }
+
+    #[test]
+    fn ties_go_to_the_higher_preset() {
+        let presets = GraphicsPreset::bundled();
+        let settings = GraphicsSettings::parse("Simulation Quality: Base");
+        let (preset, deviations) = closest_preset(&presets, &settings).unwrap();
+        assert_eq!(deviations, 0);
+        assert_eq!(preset.name, "Medium");
+    }
+
+    #[test]
+    fn no_shared_settings_have_no_closest_preset() {
+        let presets = GraphicsPreset::bundled();
+        let settings = GraphicsSettings::parse("Resolution: 1920x1080\nVSync: true");
+        assert!(closest_preset(&presets, &settings).is_none());
+        assert!(closest_preset(&presets, &GraphicsSettings::parse("")).is_none());
+    }
+}
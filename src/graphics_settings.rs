@@ -0,0 +1,123 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::system_info::parse_resolution;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Resolution(u32, u32),
+    Enum(String),
+}
+
+impl SettingValue {
+    pub fn parse(value: &str) -> SettingValue {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "true" | "on" | "enabled" | "yes" => return SettingValue::Bool(true),
+            "false" | "off" | "disabled" | "no" => return SettingValue::Bool(false),
+            _ => {}
+        }
+        if let Ok(int) = value.parse::<i64>() {
+            return SettingValue::Int(int);
+        }
+        if let Ok(float) = value.parse::<f64>() {
+            return SettingValue::Float(float);
+        }
+        if let Some((width, height)) = parse_resolution(value) {
+            return SettingValue::Resolution(width, height);
+        }
+        SettingValue::Enum(value.to_string())
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SettingValue::Bool(_) => "bool",
+            SettingValue::Int(_) => "int",
+            SettingValue::Float(_) => "float",
+            SettingValue::Resolution(_, _) => "resolution",
+            SettingValue::Enum(_) => "enum",
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SettingValue::Bool(value) => Some(*value),
+            SettingValue::Int(value) if *value == 0 || *value == 1 => Some(*value == 1),
+            _ => None,
+        }
+    }
+
+    //loose equality, the game writes some toggles as 0/1 and others as true/false
+    pub fn matches(&self, other: &SettingValue) -> bool {
+        match (self.as_bool(), other.as_bool()) {
+            (Some(a), Some(b)) => a == b,
+            _ => match (self, other) {
+                (SettingValue::Enum(a), SettingValue::Enum(b)) => a.eq_ignore_ascii_case(b),
+                (SettingValue::Float(a), SettingValue::Int(b)) | (SettingValue::Int(b), SettingValue::Float(a)) => *a == *b as f64,
+                _ => self == other,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SettingValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingValue::Bool(value) => write!(f, "{value}"),
+            SettingValue::Int(value) => write!(f, "{value}"),
+            SettingValue::Float(value) => write!(f, "{value}"),
+            SettingValue::Resolution(width, height) => write!(f, "{width}x{height}"),
+            SettingValue::Enum(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Setting {
+    pub key: String,
+    pub value: SettingValue,
+}
+
+//settings in the order the game wrote them
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphicsSettings {
+    pub entries: Vec<Setting>,
+}
+
+impl GraphicsSettings {
+    pub fn parse(settings_info: &str) -> GraphicsSettings {
+        let settings_info = settings_info.replace("Graphics Settings:", "");
+        let entries = settings_info.lines()
+            .map(str::trim)
+            .filter_map(|line| line.split_once(':').or_else(|| line.split_once('=')))
+            .filter(|(key, _)| !key.trim().is_empty())
+            .map(|(key, value)| Setting {
+                key: key.trim().to_string(),
+                value: SettingValue::parse(value),
+            })
+            .collect();
+        GraphicsSettings { entries }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&SettingValue> {
+        let key = normalize_key(key);
+        self.entries.iter()
+            .find(|setting| normalize_key(&setting.key) == key)
+            .map(|setting| &setting.value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+//"Texture Quality", "TextureQuality" and "texture_quality" all refer to the same setting
+pub fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,219 @@
+use std::fmt::Write;
+use crate::camera::CameraTransform;
+use crate::crash_export::CrashExport;
+use crate::gpu_report::GpuCrashReport;
+use crate::graphics_presets::{closest_preset, GraphicsPreset};
+use crate::graphics_settings::GraphicsSettings;
+use crate::timing::format_time;
+
+//everything is inlined so the report can be attached to a ticket and opened offline
+pub const STYLE: &str = r#"
+body { font-family: "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 70em; color: #222; background: #fafafa; }
+h1 { border-bottom: 3px solid #c00; padding-bottom: .3em; }
+h2 { margin-top: 1.6em; border-bottom: 1px solid #ccc; }
+table { border-collapse: collapse; width: 100%; margin: .5em 0; }
+th, td { text-align: left; padding: .25em .6em; border-bottom: 1px solid #e4e4e4; vertical-align: top; }
+th { background: #eee; }
+td.key { font-weight: bold; width: 14em; }
+code, .mono { font-family: Consolas, "Courier New", monospace; }
+pre { background: #f0f0f0; padding: .8em; overflow-x: auto; white-space: pre-wrap; }
+.warning { background: #fff3c4; border-left: 4px solid #e0a800; padding: .5em 1em; }
+.deviation td { background: #fff3c4; }
+.highlight { background: #fff3c4; font-weight: bold; }
+.muted { color: #888; }
+summary { cursor: pointer; font-weight: bold; margin: .5em 0; }
+.crash + .crash { margin-top: 4em; border-top: 6px double #c00; }
+"#;
+
+pub fn render(exports: &[CrashExport]) -> String {
+    render_page(exports, None)
+}
+
+//nav is inserted as is at the top of the body, e.g. a link back to a dashboard
+pub fn render_page(exports: &[CrashExport], nav: Option<&str>) -> String {
+    let mut html = String::new();
+    let title = match exports {
+        [export] => format!("Crash report: {}", export.derived.signature.text),
+        exports => format!("Crash report: {} crashes", exports.len()),
+    };
+    let _ = write!(html, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n", escape(&title));
+    if let Some(nav) = nav {
+        let _ = writeln!(html, "<nav>{nav}</nav>");
+    }
+    for export in exports {
+        render_crash(&mut html, export);
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_crash(html: &mut String, export: &CrashExport) {
+    let raw = export.raw;
+    let derived = &export.derived;
+    html.push_str("<div class=\"crash\">\n");
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&derived.signature.text));
+
+    //overview
+    html.push_str("<h2>Overview</h2>\n");
+    if derived.online.server_kind.is_private() {
+        let _ = writeln!(html, "<p class=\"warning\">Reported while connected to a private server: {}</p>", escape(&derived.online.server_kind.to_string()));
+    }
+    let mut overview = vec![
+        ("Signature".to_string(), format!("{} ({})", derived.signature.text, derived.signature.id)),
+        ("Scene".to_string(), raw.scene.to_string()),
+        ("Location".to_string(), derived.scene_info.as_ref().map(|info| info.to_string()).unwrap_or_else(|| "unknown".to_string())),
+        ("Uptime".to_string(), format!("{} ({})", derived.timing.uptime, derived.timing.bucket)),
+    ];
+    if let (Some(crash_time), Some(launch_time)) = (&derived.timing.crash_time, &derived.timing.launch_time) {
+        overview.push(("Crashed around".to_string(), format_time(crash_time)));
+        overview.push(("Launched around".to_string(), format_time(launch_time)));
+    }
+    overview.push(("Operating system".to_string(), raw.operating_system.to_string()));
+    overview.push(("Net role".to_string(), derived.online.role.to_string()));
+    overview.push(("Server version".to_string(), format!("{} ({})", raw.online_server_version, derived.online.server_kind)));
+    if let Ok(camera) = CameraTransform::parse(&raw.camera.to_string()) {
+        let [x, y, z] = camera.position;
+        let [rx, ry, rz] = camera.euler_degrees();
+        overview.push(("Camera position".to_string(), format!("{x}, {y}, {z}")));
+        overview.push(("Camera rotation".to_string(), format!("{rx}, {ry}, {rz}")));
+    }
+    key_value_table(html, &overview);
+
+    //exception
+    let exception = &derived.exception;
+    html.push_str("<h2>Exception</h2>\n");
+    let mut rows = vec![
+        ("Code".to_string(), format!("{} {}", exception.code, exception.name.as_deref().unwrap_or(""))),
+        ("Description".to_string(), exception.description.clone().unwrap_or_default()),
+        ("Flags".to_string(), exception.flags.join(", ")),
+        ("Address".to_string(), match &exception.location {
+            Some(location) => format!("{} ({location})", exception.address),
+            None => exception.address.clone(),
+        }),
+    ];
+    rows.extend(exception.interpretation.iter().map(|line| ("Interpretation".to_string(), line.clone())));
+    key_value_table(html, &rows);
+
+    //callstack
+    html.push_str("<h2>Callstack</h2>\n<table>\n<tr><th>#</th><th>Address</th><th>Location</th></tr>\n");
+    for (i, frame) in derived.frames.iter().enumerate() {
+        let location = if frame.module.is_some() { frame.location() } else { "unknown".to_string() };
+        let _ = writeln!(html, "<tr><td>{i}</td><td class=\"mono\">0x{:X}</td><td class=\"mono\">{}</td></tr>", frame.address, escape(&location));
+    }
+    html.push_str("</table>\n");
+
+    //modules
+    let _ = writeln!(html, "<h2>Modules</h2>\n<details>\n<summary>{} loaded modules</summary>\n<table>", derived.modules.len());
+    html.push_str("<tr><th>Name</th><th>Base</th><th>Size</th><th>PDB GUID</th><th>PDB age</th></tr>\n");
+    for module in &derived.modules {
+        let _ = writeln!(html, "<tr><td>{}</td><td class=\"mono\">0x{:X}</td><td class=\"mono\">0x{:X}</td><td class=\"mono\">{}</td><td>{}</td></tr>",
+            escape(&module.name), module.base, module.size, escape(&module.pdb_guid), escape(&module.pdb_age));
+    }
+    html.push_str("</table>\n</details>\n");
+
+    render_settings(html, &GraphicsSettings::parse(&raw.settings_info.to_string()));
+
+    //system
+    html.push_str("<h2>System</h2>\n");
+    let mut system = derived.system.rows();
+    system.extend(derived.system.other.iter().cloned());
+    key_value_table(html, &system);
+
+    render_gpu(html, &GpuCrashReport::parse(&raw.gpu_crash_report.to_string()));
+
+    //vr
+    html.push_str("<h2>VR</h2>\n");
+    if derived.vr.is_empty() {
+        html.push_str("<p class=\"muted\">No VR headset in use</p>\n");
+    } else {
+        let mut vr = derived.vr.rows();
+        vr.extend(derived.vr.other.iter().cloned());
+        key_value_table(html, &vr);
+    }
+
+    html.push_str("</div>\n");
+}
+
+fn render_settings(html: &mut String, settings: &GraphicsSettings) {
+    html.push_str("<h2>Graphics settings</h2>\n");
+    if settings.is_empty() {
+        html.push_str("<p class=\"muted\">No graphics settings found</p>\n");
+        return;
+    }
+    let presets = GraphicsPreset::bundled();
+    let closest = closest_preset(&presets, settings).map(|(preset, _)| preset);
+    if let Some(preset) = closest {
+        let _ = writeln!(html, "<p>Compared against the closest preset: <b>{}</b></p>", escape(&preset.name));
+    }
+    html.push_str("<table>\n<tr><th>Setting</th><th>Value</th><th>Preset</th></tr>\n");
+    for setting in &settings.entries {
+        let deviation = closest.and_then(|preset| preset.deviation(setting));
+        let expected = deviation.as_ref().map(|deviation| deviation.expected.to_string()).unwrap_or_default();
+        let class = if deviation.is_some() { " class=\"deviation\"" } else { "" };
+        let _ = writeln!(html, "<tr{class}><td>{}</td><td>{}</td><td>{}</td></tr>", escape(&setting.key), escape(&setting.value.to_string()), escape(&expected));
+    }
+    html.push_str("</table>\n");
+}
+
+fn render_gpu(html: &mut String, report: &GpuCrashReport) {
+    html.push_str("<h2>GPU</h2>\n");
+    let removed = &report.device_removed;
+    if report.adapter.is_empty() && report.breadcrumbs.is_empty() && report.page_faults.is_empty() && removed.reason.is_none() && removed.lines.is_empty() {
+        html.push_str("<p class=\"muted\">No GPU crash report</p>\n");
+        return;
+    }
+
+    let mut rows = vec![];
+    if let Some(reason) = &removed.reason {
+        rows.push(("Device removed reason".to_string(), reason.to_string()));
+    }
+    rows.extend(removed.lines.iter().cloned());
+    rows.extend(report.adapter.iter().cloned());
+    key_value_table(html, &rows);
+
+    for list in &report.breadcrumbs {
+        let last_completed = list.last_completed();
+        let _ = writeln!(html, "<details open>\n<summary>Breadcrumbs: {}</summary>\n<ol start=\"0\" class=\"mono\">", escape(&list.name));
+        for (i, op) in list.ops.iter().enumerate() {
+            let class = if Some(i) == last_completed { " class=\"highlight\"" } else { "" };
+            let _ = writeln!(html, "<li{class}>{}</li>", escape(op));
+        }
+        html.push_str("</ol>\n</details>\n");
+    }
+
+    for fault in &report.page_faults {
+        let address = fault.address.map(|address| format!("0x{address:X}")).unwrap_or_else(|| "unknown".to_string());
+        let _ = writeln!(html, "<details open>\n<summary>Page fault at {address}</summary>\n<ul class=\"mono\">");
+        for allocation in &fault.allocations {
+            let _ = writeln!(html, "<li>{}</li>", escape(allocation));
+        }
+        html.push_str("</ul>\n</details>\n");
+    }
+
+    if !report.unrecognized.is_empty() {
+        let _ = writeln!(html, "<pre>{}</pre>", escape(&report.unrecognized.join("\n")));
+    }
+}
+
+fn key_value_table(html: &mut String, rows: &[(String, String)]) {
+    html.push_str("<table>\n");
+    for (key, value) in rows {
+        let _ = writeln!(html, "<tr><td class=\"key\">{}</td><td>{}</td></tr>", escape(key), escape(value));
+    }
+    html.push_str("</table>\n");
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -7,7 +7,37 @@ mod tabs;
 mod controls;
 mod g2_crash_metrics;
 mod nt_status_enum;
+mod exception_record;
+mod modules;
+mod status_codes;
+mod commands;
+mod gpu_report;
+mod system_info;
+mod crash_export;
+mod graphics_settings;
+mod graphics_presets;
+mod settings_profile;
+mod scenes;
+mod runtime_id;
+mod camera;
+mod minimap;
+mod online;
+mod vr_info;
+mod timing;
+mod exporters;
+mod callstack;
+mod html_report;
+mod bug_report;
+mod batch;
+mod dashboard;
+mod crash_db;
+mod corpus;
+mod clustering;
+mod flamegraph;
+mod correlation;
+mod rules;
 
+use crate::commands::Command;
 use crate::crossterm::run;
 use argh::FromArgs;
 use std::{error::Error, time::Duration};
@@ -23,11 +53,21 @@ struct Cli {
     //#[argh(option, description="path to the metrics file")]
     #[argh(option, description="path to the metrics file")]
     metrics_path: Option<String>,
+    #[argh(option, description="path to a hash list used to look up runtime resource ids (default: hash_list.txt next to the executable)")]
+    hash_list: Option<String>,
+    #[argh(option, description="folder or crash database listed in the corpus tab")]
+    corpus: Option<String>,
+    #[argh(subcommand)]
+    command: Option<Command>,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let cli: Cli = argh::from_env();
+    if let Some(command) = cli.command {
+        command.run()?;
+        return Ok(());
+    }
     let tick_rate = Duration::from_millis(cli.tick_rate);
-    run(tick_rate, cli.enhanced_graphics, cli.metrics_path)?;
+    run(tick_rate, cli.enhanced_graphics, cli.metrics_path, cli.hash_list, cli.corpus)?;
     Ok(())
 }
@@ -0,0 +1,47 @@
+use std::path::Path;
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use crate::scenes::longest_match;
+
+//file next to the executable holding the per-location map data
+pub const MINIMAP_FILE: &str = "minimaps.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Landmark {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinimapEntry {
+    //case insensitive substring of the scene resource path
+    pub pattern: String,
+    //min x, min y, max x, max y in world units
+    pub bounds: [f64; 4],
+    #[serde(default)]
+    pub landmarks: Vec<Landmark>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MinimapData {
+    pub entries: Vec<MinimapEntry>,
+}
+
+impl MinimapData {
+    pub fn load(path: &Path) -> Result<MinimapData, Error> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(MinimapData { entries: serde_json::from_str(&json)? })
+    }
+
+    pub fn load_default() -> MinimapData {
+        std::env::current_exe().ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(MINIMAP_FILE)))
+            .and_then(|path| Self::load(&path).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn lookup(&self, scene: &str) -> Option<&MinimapEntry> {
+        longest_match(&self.entries, scene, |entry| &entry.pattern)
+    }
+}
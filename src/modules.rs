@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+//a loaded module as written by the game: name;base;size;pdb guid;pdb age
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Module {
+    pub name: String,
+    pub base: u64,
+    pub size: u64,
+    pub pdb_guid: String,
+    pub pdb_age: String,
+}
+
+impl Module {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.base && address - self.base < self.size
+    }
+}
+
+pub fn parse_modules(modules: &str) -> Vec<Module> {
+    let parts: Vec<&str> = modules.split(';').collect();
+    parts.chunks(5)
+        .filter(|module| module.len() == 5 && !module[0].is_empty())
+        .map(|module| Module {
+            name: module[0].to_string(),
+            base: u64::from_str_radix(module[1], 16).unwrap_or(0),
+            size: u64::from_str_radix(module[2], 16).unwrap_or(0),
+            pdb_guid: module[3].to_string(),
+            pdb_age: module[4].to_string(),
+        })
+        .collect()
+}
+
+//returns the module that contains the address, along with the offset into it
+pub fn find_module(modules: &[Module], address: u64) -> Option<(&Module, u64)> {
+    modules.iter()
+        .find(|module| module.contains(address))
+        .map(|module| (module, address - module.base))
+}
@@ -0,0 +1,234 @@
+use std::fmt;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub enum NetRole {
+    Offline,
+    Client,
+    Host,
+    Server,
+    Other(String),
+}
+
+impl NetRole {
+    pub fn parse(role: &str) -> NetRole {
+        match role.trim().to_lowercase().as_str() {
+            "" | "none" | "offline" | "local" => NetRole::Offline,
+            "client" => NetRole::Client,
+            "host" | "listenserver" | "listen_server" => NetRole::Host,
+            "server" | "dedicatedserver" | "dedicated_server" => NetRole::Server,
+            _ => NetRole::Other(role.trim().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for NetRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetRole::Offline => write!(f, "Offline"),
+            NetRole::Client => write!(f, "Client"),
+            NetRole::Host => write!(f, "Host"),
+            NetRole::Server => write!(f, "Server"),
+            NetRole::Other(role) => write!(f, "{role}"),
+        }
+    }
+}
+
+//official versions look like "8.12.0-h3", private servers tend to put their name in front
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ServerVersion {
+    pub prefix: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: Option<u32>,
+    pub suffix: String,
+}
+
+impl ServerVersion {
+    pub fn parse(version: &str) -> Option<ServerVersion> {
+        let version = version.trim();
+        let start = version.find(|c: char| c.is_ascii_digit())?;
+        let rest = &version[start..];
+        let end = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let numbers = rest[..end].trim_end_matches('.').split('.')
+            .map(|part| part.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        if numbers.len() < 2 {
+            return None;
+        }
+
+        Some(ServerVersion {
+            prefix: version_prefix(&version[..start]).to_string(),
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers.get(2).copied(),
+            suffix: rest[end..].trim_start_matches(['-', '_', ' ']).to_string(),
+        })
+    }
+}
+
+//"peacock-v6.5" has the prefix "peacock", but "dev-1.2" and "dev1.2" keep their "dev"
+fn version_prefix(prefix: &str) -> &str {
+    let separators = ['-', '_', ' '];
+    let prefix = match prefix.strip_suffix(['v', 'V']) {
+        Some(rest) if rest.is_empty() || rest.ends_with(separators) => rest,
+        _ => prefix,
+    };
+    prefix.trim_end_matches(separators)
+}
+
+impl fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(f, ".{patch}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub enum ServerKind {
+    None,
+    Official,
+    Peacock,
+    Local,
+    Unofficial(String),
+}
+
+impl ServerKind {
+    pub fn is_private(&self) -> bool {
+        matches!(self, ServerKind::Peacock | ServerKind::Local | ServerKind::Unofficial(_))
+    }
+}
+
+impl fmt::Display for ServerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerKind::None => write!(f, "none"),
+            ServerKind::Official => write!(f, "official"),
+            ServerKind::Peacock => write!(f, "Peacock"),
+            ServerKind::Local => write!(f, "local"),
+            ServerKind::Unofficial(name) => write!(f, "unofficial ({name})"),
+        }
+    }
+}
+
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "0.0.0.0"];
+//the official servers, and any host below them
+const IOI_DOMAINS: [&str; 2] = ["hitman.io", "ioi.dk"];
+//private servers that name themselves in the version string
+const PRIVATE_SERVERS: [(&str, ServerKind); 1] = [("peacock", ServerKind::Peacock)];
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OnlineInfo {
+    pub role: NetRole,
+    pub server_version: Option<ServerVersion>,
+    pub server_kind: ServerKind,
+    //role and server kind as the rest of the tool shows them, for templates
+    pub role_name: String,
+    pub server_kind_name: String,
+}
+
+impl OnlineInfo {
+    pub fn parse(net_role: &str, server_version: &str) -> OnlineInfo {
+        let version = ServerVersion::parse(server_version);
+        let role = NetRole::parse(net_role);
+        let server_kind = server_kind(server_version, version.as_ref());
+        OnlineInfo {
+            role_name: role.to_string(),
+            server_kind_name: server_kind.to_string(),
+            role,
+            server_kind,
+            server_version: version,
+        }
+    }
+}
+
+//only explicit evidence counts, an empty, offline or unreadable version isn't a private server
+fn server_kind(raw: &str, version: Option<&ServerVersion>) -> ServerKind {
+    let lower = raw.trim().to_lowercase();
+    if let Some((_, kind)) = PRIVATE_SERVERS.iter().find(|(name, _)| lower.contains(name)) {
+        return kind.clone();
+    }
+    //whole host names only, "localization.ioi.dk" isn't local
+    //host names can contain '-', which also separates them from the version, e.g. "localhost-8.12.0"
+    let hosts: Vec<&str> = lower.split(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-')
+        .flat_map(|token| if is_host(token) { vec![token] } else { token.split('-').filter(|part| is_host(part)).collect() })
+        .collect();
+    if hosts.iter().any(|host| LOCAL_HOSTS.contains(host)) {
+        return ServerKind::Local;
+    }
+    if let Some(host) = hosts.iter().find(|host| !IOI_DOMAINS.iter().any(|domain| *host == domain || host.ends_with(&format!(".{domain}")))) {
+        return ServerKind::Unofficial(host.to_string());
+    }
+    //any hosts left are ioi's
+    match version {
+        Some(version) if version.prefix.is_empty() || !hosts.is_empty() => ServerKind::Official,
+        _ => ServerKind::None,
+    }
+}
+
+//"localhost", an ip address or a dotted name with letters, but not a version like "8.12.0"
+fn is_host(token: &str) -> bool {
+    if token == "localhost" {
+        return true;
+    }
+    let parts: Vec<&str> = token.split('.').collect();
+    let is_ip = parts.len() == 4 && parts.iter().all(|part| part.parse::<u8>().is_ok());
+    let is_name = parts.len() >= 2 && parts.iter().all(|part| !part.is_empty())
+        && parts.last().map(|tld| tld.chars().all(|c| c.is_ascii_alphabetic())).unwrap_or(false);
+    is_ip || is_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(server_version: &str) -> ServerKind {
+        OnlineInfo::parse("Client", server_version).server_kind
+    }
+
+    #[test]
+    fn version_prefixes() {
+        let prefix = |version: &str| ServerVersion::parse(version).map(|version| version.prefix);
+        assert_eq!(prefix("8.12.0-h3").as_deref(), Some(""));
+        assert_eq!(prefix("v8.12.0").as_deref(), Some(""));
+        assert_eq!(prefix("peacock-v6.5.0").as_deref(), Some("peacock"));
+        assert_eq!(prefix("dev-1.2").as_deref(), Some("dev"));
+        assert_eq!(prefix("dev1.2").as_deref(), Some("dev"));
+        assert_eq!(prefix("unknown"), None);
+
+        let version = ServerVersion::parse("8.12.0-h3").unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (8, 12, Some(0)));
+        assert_eq!(version.suffix, "h3");
+    }
+
+    #[test]
+    fn offline_and_unreadable_versions_are_not_private() {
+        for version in ["", "offline", "local", "none", "unknown", "dev-1.2"] {
+            assert_eq!(kind(version), ServerKind::None, "{version}");
+            assert!(!kind(version).is_private());
+        }
+        assert_eq!(kind("8.12.0-h3"), ServerKind::Official);
+        assert_eq!(kind("pc-service.hitman.io 8.12.0"), ServerKind::Official);
+        assert_eq!(kind("localization.ioi.dk 8.12.0"), ServerKind::Official);
+    }
+
+    #[test]
+    fn private_servers() {
+        assert_eq!(kind("peacock-v6.5.0"), ServerKind::Peacock);
+        assert_eq!(kind("localhost-8.12.0"), ServerKind::Local);
+        assert_eq!(kind("http://127.0.0.1:8000 8.12.0"), ServerKind::Local);
+        assert_eq!(kind("hitman.example.com-8.12.0"), ServerKind::Unofficial("hitman.example.com".to_string()));
+    }
+
+    #[test]
+    fn net_roles() {
+        assert_eq!(NetRole::parse(""), NetRole::Offline);
+        assert_eq!(NetRole::parse("Client"), NetRole::Client);
+        assert_eq!(NetRole::parse("listen_server"), NetRole::Host);
+        assert_eq!(NetRole::parse("spectator"), NetRole::Other("spectator".to_string()));
+    }
+}
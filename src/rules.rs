@@ -0,0 +1,219 @@
+use std::path::Path;
+use anyhow::{anyhow, Error};
+use serde::Deserialize;
+use crate::crash_export::CrashExport;
+use crate::system_info::first_number;
+
+static BUNDLED_RULES: &str = include_str!("../assets/rules.json");
+
+//file next to the executable that adds rules to the bundled ones
+pub const USER_RULES_FILE: &str = "rules.json";
+
+//a known crash pattern, it applies when every condition holds
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub diagnosis: String,
+    pub conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Condition {
+    //one of FIELDS
+    pub field: String,
+    //case insensitive substrings, any of them has to occur in the value
+    #[serde(default)]
+    pub contains: Vec<String>,
+    //bounds on the first number in the value
+    #[serde(default)]
+    pub below: Option<f64>,
+    #[serde(default)]
+    pub above: Option<f64>,
+}
+
+//the crash properties conditions can test
+pub const FIELDS: [&str; 19] = [
+    "signature", "exception", "module", "scene", "os",
+    "cpu", "cpu_cores", "cpu_threads", "ram_mb", "gpu", "gpu_vendor", "vram_mb", "driver",
+    "vr_runtime", "vr_headset", "vr_vendor", "vr_refresh_hz", "vr_resolution", "vr_tracking",
+];
+
+impl Condition {
+    fn matches(&self, export: &CrashExport) -> bool {
+        let value = match field_value(export, &self.field) {
+            Some(value) => value,
+            None => return false,
+        };
+        let lower = value.to_lowercase();
+        if !self.contains.is_empty() && !self.contains.iter().any(|part| lower.contains(&part.to_lowercase())) {
+            return false;
+        }
+        if self.below.is_some() || self.above.is_some() {
+            let number = match first_number(&value) {
+                Some(number) => number,
+                None => return false,
+            };
+            if self.below.map(|below| number >= below).unwrap_or(false) || self.above.map(|above| number <= above).unwrap_or(false) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+//values that are missing from the crash never match
+fn field_value(export: &CrashExport, field: &str) -> Option<String> {
+    let derived = &export.derived;
+    let system = &derived.system;
+    match field {
+        "signature" => Some(derived.signature.text.clone()),
+        "exception" => Some(format!("{} {}", derived.exception.code, derived.exception.name.as_deref().unwrap_or(""))),
+        "module" => derived.faulting_module(),
+        "scene" => Some(export.raw.scene.to_string()),
+        "os" => Some(export.raw.operating_system.to_string()).filter(|os| !os.is_empty()),
+        "cpu" => system.cpu.clone(),
+        "cpu_cores" => system.cpu_cores.map(|cores| cores.to_string()),
+        "cpu_threads" => system.cpu_threads.map(|threads| threads.to_string()),
+        "ram_mb" => system.ram_mb.map(|mb| mb.to_string()),
+        "gpu" => Some(system.gpus.join("; ")).filter(|gpus| !gpus.is_empty()),
+        "gpu_vendor" => system.gpu_vendor().map(str::to_string),
+        "vram_mb" => system.vram_mb.map(|mb| mb.to_string()),
+        "driver" => system.driver_version.clone(),
+        "vr_runtime" => derived.vr.runtime.clone(),
+        "vr_headset" => derived.vr.hmd_model.clone(),
+        "vr_vendor" => derived.vr.hmd_vendor.clone(),
+        "vr_refresh_hz" => derived.vr.refresh_rate_hz.map(|hz| hz.to_string()),
+        "vr_resolution" => derived.vr.render_resolution.map(|(width, height)| format!("{width}x{height}")),
+        "vr_tracking" => derived.vr.tracking.clone(),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn bundled() -> RuleSet {
+        RuleSet {
+            rules: serde_json::from_str(BUNDLED_RULES).unwrap_or_default(),
+        }
+    }
+
+    //the bundled rules, after the user rules next to the executable when there are any
+    pub fn load_default() -> RuleSet {
+        let mut set = Self::bundled();
+        let user_rules = std::env::current_exe().ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(USER_RULES_FILE)))
+            .filter(|path| path.exists());
+        if let Some(path) = user_rules {
+            match Self::read_rules(&path) {
+                Ok(mut rules) => {
+                    rules.append(&mut set.rules);
+                    set.rules = rules;
+                }
+                Err(e) => eprintln!("ignoring {}: {e}", path.display()),
+            }
+        }
+        set
+    }
+
+    //a misspelled field would silently never match, so it is an error instead
+    pub fn read_rules(path: &Path) -> Result<Vec<Rule>, Error> {
+        let json = std::fs::read_to_string(path)?;
+        let rules: Vec<Rule> = serde_json::from_str(&json)?;
+        for rule in &rules {
+            if let Some(condition) = rule.conditions.iter().find(|condition| !FIELDS.contains(&condition.field.as_str())) {
+                return Err(anyhow!("rule '{}' tests the unknown field '{}', expected one of {}", rule.name, condition.field, FIELDS.join(", ")));
+            }
+        }
+        Ok(rules)
+    }
+
+    pub fn diagnose(&self, export: &CrashExport) -> Vec<&Rule> {
+        self.rules.iter()
+            .filter(|rule| !rule.conditions.is_empty() && rule.conditions.iter().all(|condition| condition.matches(export)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::g2_crash_metrics::{G2CrashMetrics, NullStringS};
+    use crate::scenes::SceneTable;
+
+    fn crash(system_info: &str) -> G2CrashMetrics {
+        G2CrashMetrics {
+            system_info: NullStringS::from_str(system_info).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn rule(conditions: Vec<Condition>) -> RuleSet {
+        RuleSet { rules: vec![Rule { name: "test".to_string(), diagnosis: String::new(), conditions }] }
+    }
+
+    fn condition(field: &str, contains: &[&str], below: Option<f64>, above: Option<f64>) -> Condition {
+        Condition { field: field.to_string(), contains: contains.iter().map(|part| part.to_string()).collect(), below, above }
+    }
+
+    fn matches(set: &RuleSet, metrics: &G2CrashMetrics) -> bool {
+        !set.diagnose(&CrashExport::new(metrics, &SceneTable::bundled(), None)).is_empty()
+    }
+
+    #[test]
+    fn bounds_test_the_number_in_the_value() {
+        let low_ram = crash("RAM: 4096 MB");
+        let enough_ram = crash("RAM: 16384 MB");
+        let set = rule(vec![condition("ram_mb", &[], Some(7680.0), None)]);
+        assert!(matches(&set, &low_ram));
+        assert!(!matches(&set, &enough_ram));
+        let set = rule(vec![condition("ram_mb", &[], None, Some(8192.0))]);
+        assert!(!matches(&set, &low_ram));
+        assert!(matches(&set, &enough_ram));
+    }
+
+    #[test]
+    fn contains_is_case_insensitive_and_any_part_matches() {
+        let metrics = crash("GPU: NVIDIA GeForce RTX 3080");
+        assert!(matches(&rule(vec![condition("gpu", &["radeon", "geforce"], None, None)]), &metrics));
+        assert!(!matches(&rule(vec![condition("gpu", &["radeon"], None, None)]), &metrics));
+    }
+
+    #[test]
+    fn every_condition_has_to_hold() {
+        let metrics = crash("RAM: 4096 MB\nGPU: NVIDIA GeForce RTX 3080");
+        let both = rule(vec![condition("ram_mb", &[], Some(7680.0), None), condition("gpu", &["nvidia"], None, None)]);
+        let one = rule(vec![condition("ram_mb", &[], Some(7680.0), None), condition("gpu", &["radeon"], None, None)]);
+        assert!(matches(&both, &metrics));
+        assert!(!matches(&one, &metrics));
+    }
+
+    #[test]
+    fn missing_values_and_empty_rules_never_match() {
+        let metrics = crash("GPU: NVIDIA GeForce RTX 3080");
+        assert!(!matches(&rule(vec![condition("ram_mb", &[], Some(7680.0), None)]), &metrics));
+        assert!(!matches(&rule(vec![]), &metrics));
+    }
+
+    #[test]
+    fn bundled_rules_diagnose_low_memory() {
+        let set = RuleSet::bundled();
+        assert!(!set.rules.is_empty());
+        let metrics = crash("RAM: 4096 MB");
+        let export = CrashExport::new(&metrics, &SceneTable::bundled(), None);
+        let names: Vec<&str> = set.diagnose(&export).iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(names, vec!["below minimum RAM"]);
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let path = std::env::temp_dir().join(format!("rules-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{ "name": "typo", "diagnosis": "", "conditions": [{ "field": "ram", "below": 1 }] }]"#).unwrap();
+        let result = RuleSet::read_rules(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.unwrap_err().to_string().contains("unknown field 'ram'"));
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::Path;
+use anyhow::Error;
+use md5::{Digest, Md5};
+
+//default hash list location, next to the executable
+pub const HASH_LIST_FILE: &str = "hash_list.txt";
+
+//the runtime resource id is the first 8 bytes of the md5 of the lowercase path, with the top byte cleared
+pub fn runtime_id(path: &str) -> u64 {
+    let digest = Md5::digest(path.to_lowercase().as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes) & 0x00FF_FFFF_FFFF_FFFF
+}
+
+pub fn format_id(id: u64) -> String {
+    format!("{id:016X}")
+}
+
+pub fn parse_id(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    let hex = hex.split('.').next().unwrap_or(hex);
+    if hex.len() == 16 {
+        u64::from_str_radix(hex, 16).ok()
+    } else {
+        None
+    }
+}
+
+//the scene field holds the bare entity path, the bricks are addressed by their resource paths
+pub fn scene_resource_paths(scene: &str) -> Vec<String> {
+    if scene.is_empty() {
+        return vec![];
+    }
+    if scene.starts_with('[') {
+        return vec![scene.to_string()];
+    }
+    vec![
+        format!("[{scene}].pc_entitytype"),
+        format!("[{scene}].pc_entitytemplate"),
+    ]
+}
+
+//a hash list as distributed for RPKG tools, one "HASH.TYPE,path" entry per line
+#[derive(Debug, Default)]
+pub struct HashList {
+    pub entries: HashMap<u64, String>,
+}
+
+impl HashList {
+    pub fn load(path: &Path) -> Result<HashList, Error> {
+        let text = std::fs::read_to_string(path)?;
+        let entries = text.lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(','))
+            .filter_map(|(hash, resource)| parse_id(hash).map(|id| (id, resource.trim().to_string())))
+            .collect();
+        Ok(HashList { entries })
+    }
+
+    pub fn load_default() -> Option<HashList> {
+        let path = std::env::current_exe().ok()?.parent()?.join(HASH_LIST_FILE);
+        Self::load(&path).ok()
+    }
+
+    pub fn lookup(&self, id: u64) -> Option<&str> {
+        self.entries.get(&id).map(|path| path.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARIS: &str = "[assembly:/_pro/scenes/missions/paris/_scene_paris.entity].pc_entitytype";
+
+    #[test]
+    fn runtime_id_is_the_masked_md5() {
+        //the md5 starts with fd20243e8f197dcf, the top byte is cleared
+        assert_eq!(runtime_id(PARIS), 0x0020_243E_8F19_7DCF);
+        assert_eq!(runtime_id(&PARIS.to_uppercase()), runtime_id(PARIS));
+        assert_eq!(format_id(runtime_id(PARIS)), "0020243E8F197DCF");
+    }
+
+    #[test]
+    fn ids_are_parsed_from_hash_list_entries() {
+        assert_eq!(parse_id("0020243E8F197DCF.TEMP"), Some(0x0020_243E_8F19_7DCF));
+        assert_eq!(parse_id(" 0x0020243e8f197dcf "), Some(0x0020_243E_8F19_7DCF));
+        assert_eq!(parse_id("20243E8F197DCF"), None);
+        assert_eq!(parse_id("not a hash"), None);
+    }
+
+    #[test]
+    fn scene_resource_paths_cover_type_and_template() {
+        let paths = scene_resource_paths("assembly:/_pro/scenes/missions/paris/_scene_paris.entity");
+        assert_eq!(paths, vec![PARIS.to_string(), PARIS.replace("pc_entitytype", "pc_entitytemplate")]);
+        assert_eq!(scene_resource_paths(PARIS), vec![PARIS.to_string()]);
+        assert!(scene_resource_paths("").is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::path::Path;
+use anyhow::Error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static BUNDLED_SCENES: &str = include_str!("../assets/scenes.json");
+
+//file next to the executable that extends or overrides the bundled table
+pub const USER_SCENES_FILE: &str = "scenes.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneEntry {
+    //case insensitive substring of the scene resource path
+    pub pattern: String,
+    pub location: String,
+    pub mission: String,
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SceneInfo {
+    pub location: String,
+    pub mission: String,
+    pub mode: String,
+}
+
+impl fmt::Display for SceneInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} / {} / {}", self.location, self.mission, self.mode)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SceneTable {
+    pub entries: Vec<SceneEntry>,
+}
+
+impl SceneTable {
+    pub fn bundled() -> SceneTable {
+        SceneTable {
+            entries: serde_json::from_str(BUNDLED_SCENES).unwrap_or_default(),
+        }
+    }
+
+    //the bundled table, extended with the user table next to the executable when there is one
+    pub fn load_default() -> SceneTable {
+        let mut table = Self::bundled();
+        let user_table = std::env::current_exe().ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(USER_SCENES_FILE)))
+            .filter(|path| path.exists());
+        if let Some(path) = user_table {
+            if let Ok(user_entries) = Self::read_entries(&path) {
+                table.extend(user_entries);
+            }
+        }
+        table
+    }
+
+    pub fn read_entries(path: &Path) -> Result<Vec<SceneEntry>, Error> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    //user entries come first so they win over bundled entries with the same pattern
+    pub fn extend(&mut self, entries: Vec<SceneEntry>) {
+        let mut merged = entries;
+        merged.append(&mut self.entries);
+        self.entries = merged;
+    }
+
+    pub fn lookup(&self, scene: &str) -> Option<SceneInfo> {
+        let lower = scene.to_lowercase();
+        let entry = longest_match(&self.entries, scene, |entry| &entry.pattern)?;
+
+        Some(SceneInfo {
+            location: entry.location.clone(),
+            mission: entry.mission.clone(),
+            mode: entry.mode.clone().unwrap_or_else(|| game_mode(&lower).to_string()),
+        })
+    }
+}
+
+//the entry with the longest pattern contained in the scene path, earlier entries win ties
+pub fn longest_match<'a, T>(entries: &'a [T], scene: &str, pattern: impl Fn(&T) -> &str) -> Option<&'a T> {
+    let scene = scene.to_lowercase();
+    entries.iter()
+        .filter(|entry| scene.contains(&pattern(entry).to_lowercase()))
+        .fold(None, |best: Option<&T>, entry| match best {
+            Some(best) if pattern(best).len() >= pattern(entry).len() => Some(best),
+            _ => Some(entry),
+        })
+}
+
+fn game_mode(scene: &str) -> &'static str {
+    if scene.contains("contract") {
+        "Contract"
+    } else if scene.contains("escalation") {
+        "Escalation"
+    } else if scene.contains("elusive") || scene.contains("_et_") {
+        "Elusive Target"
+    } else if scene.contains("sniper") {
+        "Sniper Assassin"
+    } else if scene.contains("evergreen") || scene.contains("freelancer") {
+        "Freelancer"
+    } else if scene.contains("versus") || scene.contains("ghost") {
+        "Ghost Mode"
+    } else if scene.contains("bonus") || scene.contains("special") {
+        "Special Assignment"
+    } else {
+        "Mission"
+    }
+}
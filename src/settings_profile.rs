@@ -0,0 +1,93 @@
+use std::path::Path;
+use anyhow::{anyhow, Error};
+use crate::graphics_settings::{GraphicsSettings, Setting, SettingValue};
+
+pub const REGISTRY_KEY: &str = r"HKEY_CURRENT_USER\SOFTWARE\IO Interactive\HITMAN3";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    Registry,
+    KeyValue,
+}
+
+impl ProfileFormat {
+    pub fn from_name(name: &str) -> Option<ProfileFormat> {
+        match name {
+            "reg" | "registry" => Some(ProfileFormat::Registry),
+            "cfg" | "profile" | "ini" | "txt" => Some(ProfileFormat::KeyValue),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<ProfileFormat> {
+        path.extension().and_then(|extension| extension.to_str()).and_then(Self::from_name)
+    }
+
+    pub fn write(self, settings: &GraphicsSettings) -> String {
+        match self {
+            ProfileFormat::Registry => to_registry(settings),
+            ProfileFormat::KeyValue => to_key_value(settings),
+        }
+    }
+}
+
+//registry value names can't contain the separators the game uses in settings_info
+fn value_name(key: &str) -> String {
+    key.split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn to_registry(settings: &GraphicsSettings) -> String {
+    let mut reg = String::from("Windows Registry Editor Version 5.00\r\n\r\n");
+    reg.push_str(&format!("[{REGISTRY_KEY}]\r\n"));
+    for setting in &settings.entries {
+        let name = value_name(&setting.key);
+        match &setting.value {
+            SettingValue::Bool(value) => reg.push_str(&format!("\"{name}\"=dword:{:08x}\r\n", *value as u32)),
+            SettingValue::Int(value) if *value >= 0 && *value <= u32::MAX as i64 => reg.push_str(&format!("\"{name}\"=dword:{value:08x}\r\n")),
+            SettingValue::Resolution(width, height) => {
+                reg.push_str(&format!("\"{name}Width\"=dword:{width:08x}\r\n"));
+                reg.push_str(&format!("\"{name}Height\"=dword:{height:08x}\r\n"));
+            }
+            value => reg.push_str(&format!("\"{name}\"=\"{}\"\r\n", value.to_string().replace('\\', "\\\\").replace('"', "\\\""))),
+        }
+    }
+    reg
+}
+
+fn to_key_value(settings: &GraphicsSettings) -> String {
+    let mut profile = String::from("# HITMAN 3 graphics settings profile\n");
+    for setting in &settings.entries {
+        profile.push_str(&format!("{}={}\n", setting.key, setting.value));
+    }
+    profile
+}
+
+pub fn parse_key_value(profile: &str) -> GraphicsSettings {
+    let entries = profile.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';') && !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| Setting {
+            key: key.trim().to_string(),
+            value: SettingValue::parse(value),
+        })
+        .collect();
+    GraphicsSettings { entries }
+}
+
+pub fn read_profile(path: &Path) -> Result<GraphicsSettings, Error> {
+    if ProfileFormat::from_path(path) != Some(ProfileFormat::KeyValue) {
+        return Err(anyhow!("{} is not a key=value settings profile", path.display()));
+    }
+    let profile = std::fs::read_to_string(path)?;
+    Ok(parse_key_value(&profile))
+}
@@ -0,0 +1,524 @@
+use std::fmt;
+use phf::phf_map;
+use crate::nt_status_enum::NTSTATUS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeKind {
+    NtStatus,
+    HResult,
+    Win32,
+}
+
+impl fmt::Display for CodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodeKind::NtStatus => write!(f, "NTSTATUS"),
+            CodeKind::HResult => write!(f, "HRESULT"),
+            CodeKind::Win32 => write!(f, "Win32 error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Success,
+    Informational,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Success => write!(f, "success"),
+            Severity::Informational => write!(f, "informational"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+//a single entry in one of the known code tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownCode {
+    pub code: u32,
+    pub kind: CodeKind,
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCode {
+    pub code: u32,
+    pub kind: CodeKind,
+    pub severity: Severity,
+    pub customer: bool,
+    pub facility: u16,
+    pub facility_name: Option<&'static str>,
+    pub code_value: u16,
+    pub known: Option<KnownCode>,
+}
+
+impl DecodedCode {
+    pub fn new(code: u32, kind: CodeKind) -> Self {
+        let (severity, facility, facility_name) = match kind {
+            CodeKind::NtStatus => {
+                let severity = match code >> 30 {
+                    0 => Severity::Success,
+                    1 => Severity::Informational,
+                    2 => Severity::Warning,
+                    _ => Severity::Error,
+                };
+                let facility = ((code >> 16) & 0xFFF) as u16;
+                (severity, facility, NT_FACILITIES.get(&facility).copied())
+            }
+            CodeKind::HResult => {
+                let severity = if code >> 31 == 1 { Severity::Error } else { Severity::Success };
+                let facility = ((code >> 16) & 0x1FFF) as u16;
+                (severity, facility, HRESULT_FACILITIES.get(&facility).copied())
+            }
+            CodeKind::Win32 => {
+                let severity = if code == 0 { Severity::Success } else { Severity::Error };
+                (severity, 0, None)
+            }
+        };
+
+        DecodedCode {
+            code,
+            kind,
+            severity,
+            customer: kind != CodeKind::Win32 && code & 0x20000000 != 0,
+            facility,
+            facility_name,
+            code_value: (code & 0xFFFF) as u16,
+            known: lookup_code(code, kind),
+        }
+    }
+
+    //decodes a code of unknown origin by checking the tables first and the bit layout second
+    pub fn guess(code: u32) -> Self {
+        if let Some(known) = lookup_code_any(code) {
+            return Self::new(code, known.kind);
+        }
+        if code <= 0xFFFF {
+            return Self::new(code, CodeKind::Win32);
+        }
+        let hresult_facility = ((code >> 16) & 0x1FFF) as u16;
+        if code & 0x10000000 == 0 && HRESULT_FACILITIES.contains_key(&hresult_facility) && !NT_FACILITIES.contains_key(&(hresult_facility & 0xFFF)) {
+            return Self::new(code, CodeKind::HResult);
+        }
+        Self::new(code, CodeKind::NtStatus)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        self.known.map(|known| known.name)
+    }
+
+    pub fn description(&self) -> Option<&'static str> {
+        self.known.and_then(|known| known.description)
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = match self.kind {
+            CodeKind::Win32 => vec![format!("{}: {} ({})", self.kind, self.code, self.name().unwrap_or("unknown"))],
+            _ => vec![format!("{}: 0x{:08X} ({})", self.kind, self.code, self.name().unwrap_or("unknown"))],
+        };
+        if let Some(description) = self.description() {
+            lines.push(format!("description: {description}"));
+        }
+        if self.kind != CodeKind::Win32 {
+            lines.push(format!("severity: {}", self.severity));
+            lines.push(format!("customer defined: {}", self.customer));
+            lines.push(format!("facility: 0x{:x} ({})", self.facility, self.facility_name.unwrap_or("unknown")));
+            lines.push(format!("code: 0x{:x}", self.code_value));
+        }
+        if self.kind == CodeKind::HResult && self.facility == FACILITY_WIN32 {
+            let win32 = Self::new(self.code_value as u32, CodeKind::Win32);
+            lines.push(format!("wraps Win32 error {} ({})", win32.code, win32.name().unwrap_or("unknown")));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for DecodedCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "0x{:x} ({name})", self.code),
+            None => write!(f, "0x{:x}", self.code),
+        }
+    }
+}
+
+pub fn lookup_code(code: u32, kind: CodeKind) -> Option<KnownCode> {
+    match kind {
+        CodeKind::NtStatus => NTSTATUS.get(&code).map(|name| KnownCode {
+            code,
+            kind,
+            name,
+            description: NTSTATUS_DESCRIPTIONS.get(&code).copied(),
+        }),
+        CodeKind::HResult => HRESULT.get(&code)
+            .or_else(|| DXGI_D3D12.get(&code))
+            .map(|(name, description)| KnownCode { code, kind, name, description: Some(description) }),
+        CodeKind::Win32 => WIN32_ERRORS.get(&code)
+            .map(|(name, description)| KnownCode { code, kind, name, description: Some(description) }),
+    }
+}
+
+pub fn lookup_code_any(code: u32) -> Option<KnownCode> {
+    [CodeKind::NtStatus, CodeKind::HResult, CodeKind::Win32].iter()
+        .find_map(|kind| lookup_code(code, *kind))
+}
+
+fn all_codes() -> impl Iterator<Item = KnownCode> {
+    let nt = NTSTATUS.entries().map(|(code, name)| KnownCode {
+        code: *code,
+        kind: CodeKind::NtStatus,
+        name,
+        description: NTSTATUS_DESCRIPTIONS.get(code).copied(),
+    });
+    let hresult = HRESULT.entries().chain(DXGI_D3D12.entries()).map(|(code, (name, description))| KnownCode {
+        code: *code,
+        kind: CodeKind::HResult,
+        name,
+        description: Some(description),
+    });
+    let win32 = WIN32_ERRORS.entries().map(|(code, (name, description))| KnownCode {
+        code: *code,
+        kind: CodeKind::Win32,
+        name,
+        description: Some(description),
+    });
+    nt.chain(hresult).chain(win32)
+}
+
+//reverse lookup, an exact (case insensitive) name match wins over partial matches
+pub fn search_name(query: &str) -> Vec<KnownCode> {
+    let query = query.to_uppercase();
+    if let Some(exact) = all_codes().find(|known| known.name == query) {
+        return vec![exact];
+    }
+    let mut matches: Vec<KnownCode> = all_codes().filter(|known| known.name.contains(&query)).collect();
+    matches.sort_by_key(|known| known.name);
+    matches
+}
+
+pub fn parse_code(value: &str) -> Option<u32> {
+    let value = value.trim();
+    match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u32>().ok().or_else(|| value.parse::<i32>().ok().map(|v| v as u32)),
+    }
+}
+
+pub const FACILITY_WIN32: u16 = 0x7;
+
+static NT_FACILITIES: phf::Map<u16, &'static str> = phf_map! {
+    0x0u16 => "FACILITY_NONE",
+    0x1u16 => "FACILITY_DEBUGGER",
+    0x2u16 => "FACILITY_RPC_RUNTIME",
+    0x3u16 => "FACILITY_RPC_STUBS",
+    0x4u16 => "FACILITY_IO_ERROR_CODE",
+    0x7u16 => "FACILITY_NTWIN32",
+    0x9u16 => "FACILITY_NTSSPI",
+    0xAu16 => "FACILITY_TERMINAL_SERVER",
+    0x10u16 => "FACILITY_USB_ERROR_CODE",
+    0x11u16 => "FACILITY_HID_ERROR_CODE",
+    0x12u16 => "FACILITY_FIREWIRE_ERROR_CODE",
+    0x13u16 => "FACILITY_CLUSTER_ERROR_CODE",
+    0x14u16 => "FACILITY_ACPI_ERROR_CODE",
+    0x15u16 => "FACILITY_SXS_ERROR_CODE",
+    0x19u16 => "FACILITY_TRANSACTION",
+    0x1Au16 => "FACILITY_COMMONLOG",
+    0x1Bu16 => "FACILITY_VIDEO",
+    0x1Cu16 => "FACILITY_FILTER_MANAGER",
+    0x1Du16 => "FACILITY_MONITOR",
+    0x1Eu16 => "FACILITY_GRAPHICS_KERNEL",
+    0x20u16 => "FACILITY_DRIVER_FRAMEWORK",
+    0x21u16 => "FACILITY_FVE_ERROR_CODE",
+    0x22u16 => "FACILITY_FWP_ERROR_CODE",
+    0x23u16 => "FACILITY_NDIS_ERROR_CODE",
+    0x35u16 => "FACILITY_HYPERVISOR",
+    0x36u16 => "FACILITY_IPSEC",
+    0x37u16 => "FACILITY_VIRTUALIZATION",
+    0x38u16 => "FACILITY_VOLMGR",
+    0x39u16 => "FACILITY_BCD_ERROR_CODE",
+    0x3Eu16 => "FACILITY_WIN32K_NTUSER",
+    0x3Fu16 => "FACILITY_WIN32K_NTGDI",
+    0x40u16 => "FACILITY_RESUME_KEY_FILTER",
+    0x41u16 => "FACILITY_RDBSS",
+    0x42u16 => "FACILITY_BTH_ATT",
+    0x43u16 => "FACILITY_SECUREBOOT",
+    0x44u16 => "FACILITY_AUDIO_KERNEL",
+    0x45u16 => "FACILITY_VSM",
+    0x50u16 => "FACILITY_VOLSNAP",
+    0x51u16 => "FACILITY_SDBUS",
+    0x5Cu16 => "FACILITY_SHARED_VHDX",
+    0x5Du16 => "FACILITY_SMB",
+    0x99u16 => "FACILITY_INTERIX",
+    0xE7u16 => "FACILITY_SPACES",
+    0xE8u16 => "FACILITY_SECURITY_CORE",
+    0xE9u16 => "FACILITY_SYSTEM_INTEGRITY",
+    0xEAu16 => "FACILITY_LICENSING",
+    0xECu16 => "FACILITY_APPEXEC",
+};
+
+static HRESULT_FACILITIES: phf::Map<u16, &'static str> = phf_map! {
+    0u16 => "FACILITY_NULL",
+    1u16 => "FACILITY_RPC",
+    2u16 => "FACILITY_DISPATCH",
+    3u16 => "FACILITY_STORAGE",
+    4u16 => "FACILITY_ITF",
+    7u16 => "FACILITY_WIN32",
+    8u16 => "FACILITY_WINDOWS",
+    9u16 => "FACILITY_SECURITY",
+    10u16 => "FACILITY_CONTROL",
+    11u16 => "FACILITY_CERT",
+    12u16 => "FACILITY_INTERNET",
+    13u16 => "FACILITY_MEDIASERVER",
+    14u16 => "FACILITY_MSMQ",
+    15u16 => "FACILITY_SETUPAPI",
+    16u16 => "FACILITY_SCARD",
+    17u16 => "FACILITY_COMPLUS",
+    18u16 => "FACILITY_AAF",
+    19u16 => "FACILITY_URT",
+    20u16 => "FACILITY_ACS",
+    21u16 => "FACILITY_DPLAY",
+    22u16 => "FACILITY_UMI",
+    23u16 => "FACILITY_SXS",
+    24u16 => "FACILITY_WINDOWS_CE",
+    25u16 => "FACILITY_HTTP",
+    26u16 => "FACILITY_USERMODE_COMMONLOG",
+    31u16 => "FACILITY_USERMODE_FILTER_MANAGER",
+    32u16 => "FACILITY_BACKGROUNDCOPY",
+    33u16 => "FACILITY_CONFIGURATION",
+    34u16 => "FACILITY_STATE_MANAGEMENT",
+    35u16 => "FACILITY_METADIRECTORY",
+    36u16 => "FACILITY_WINDOWSUPDATE",
+    37u16 => "FACILITY_DIRECTORYSERVICE",
+    38u16 => "FACILITY_GRAPHICS",
+    39u16 => "FACILITY_SHELL",
+    40u16 => "FACILITY_TPM_SERVICES",
+    41u16 => "FACILITY_TPM_SOFTWARE",
+    48u16 => "FACILITY_PLA",
+    49u16 => "FACILITY_FVE",
+    50u16 => "FACILITY_FWP",
+    51u16 => "FACILITY_WINRM",
+    52u16 => "FACILITY_NDIS",
+    53u16 => "FACILITY_USERMODE_HYPERVISOR",
+    54u16 => "FACILITY_CMI",
+    55u16 => "FACILITY_USERMODE_VIRTUALIZATION",
+    56u16 => "FACILITY_USERMODE_VOLMGR",
+    57u16 => "FACILITY_BCD",
+    58u16 => "FACILITY_USERMODE_VHD",
+    60u16 => "FACILITY_SDIAG",
+    61u16 => "FACILITY_WEBSERVICES",
+    80u16 => "FACILITY_WINDOWS_DEFENDER",
+    81u16 => "FACILITY_OPC",
+    0x876u16 => "FACILITY_D3D10",
+    0x877u16 => "FACILITY_D3DX",
+    0x878u16 => "FACILITY_DSOUND",
+    0x879u16 => "FACILITY_XAUDIO2",
+    0x87Au16 => "FACILITY_DXGI",
+    0x87Bu16 => "FACILITY_DXGI_DDI",
+    0x87Cu16 => "FACILITY_D3D11",
+    0x87Du16 => "FACILITY_D2D",
+    0x87Eu16 => "FACILITY_D3D12",
+    0x88Bu16 => "FACILITY_XINPUT",
+    0x898u16 => "FACILITY_DWRITE",
+};
+
+static NTSTATUS_DESCRIPTIONS: phf::Map<u32, &'static str> = phf_map! {
+    0x00000000u32 => "The operation completed successfully.",
+    0x80000001u32 => "A guard page was accessed, usually while growing the stack.",
+    0x80000002u32 => "A load or store was performed on an address that is not properly aligned.",
+    0x80000003u32 => "A breakpoint was hit, usually an assertion or a debug break left in the build.",
+    0x80000004u32 => "A single step trap was triggered by a debugger or the trap flag.",
+    0xC0000005u32 => "The instruction referenced memory that could not be read, written or executed.",
+    0xC0000006u32 => "The page could not be paged in, usually because the backing file or disk is unavailable.",
+    0xC0000008u32 => "An invalid handle was specified.",
+    0xC0000017u32 => "Not enough virtual memory or paging file quota is available.",
+    0xC000001Du32 => "An attempt was made to execute an illegal instruction, often an unsupported CPU extension.",
+    0xC0000025u32 => "Execution was resumed after a noncontinuable exception.",
+    0xC0000026u32 => "An exception handler returned an invalid disposition.",
+    0xC000008Cu32 => "An array index exceeded its bounds.",
+    0xC000008Du32 => "A floating point operation used a denormal operand.",
+    0xC000008Eu32 => "A floating point division by zero was attempted.",
+    0xC000008Fu32 => "The result of a floating point operation cannot be represented exactly.",
+    0xC0000090u32 => "An invalid floating point operation was performed.",
+    0xC0000091u32 => "A floating point operation overflowed.",
+    0xC0000092u32 => "The floating point stack overflowed or underflowed.",
+    0xC0000093u32 => "A floating point operation underflowed.",
+    0xC0000094u32 => "An integer division by zero was attempted.",
+    0xC0000095u32 => "An integer operation overflowed.",
+    0xC0000096u32 => "An attempt was made to execute a privileged instruction.",
+    0xC00000FDu32 => "The thread used up its stack, usually because of unbounded recursion.",
+    0xC0000135u32 => "A required DLL could not be found.",
+    0xC000013Au32 => "The application was terminated by a Ctrl+C.",
+    0xC0000142u32 => "A DLL failed to initialize.",
+    0xC0000194u32 => "A possible deadlock condition was detected.",
+    0xC00002B4u32 => "Multiple floating point faults occurred.",
+    0xC00002B5u32 => "Multiple floating point traps occurred.",
+    0xC00002B6u32 => "The device was removed.",
+    0xC0000374u32 => "The heap has been corrupted.",
+    0xC0000409u32 => "A security check detected a stack based buffer overrun, or the process called __fastfail.",
+    0xC0000417u32 => "An invalid parameter was passed to a C runtime function.",
+    0xC0000420u32 => "An assertion failure occurred.",
+    0xC0000428u32 => "The hash of the image is invalid, the file may have been modified.",
+    0xC0000602u32 => "A fail fast exception occurred, exception handlers were not invoked.",
+    0xC015000Fu32 => "The activation context being deactivated is not the most recently activated one.",
+};
+
+static HRESULT: phf::Map<u32, (&'static str, &'static str)> = phf_map! {
+    0x00000000u32 => ("S_OK", "The operation completed successfully."),
+    0x00000001u32 => ("S_FALSE", "The operation completed successfully but returned false."),
+    0x8000000Au32 => ("E_PENDING", "The data necessary to complete this operation is not yet available."),
+    0x8000000Bu32 => ("E_BOUNDS", "The operation attempted to access data outside the valid range."),
+    0x80004001u32 => ("E_NOTIMPL", "Not implemented."),
+    0x80004002u32 => ("E_NOINTERFACE", "No such interface supported."),
+    0x80004003u32 => ("E_POINTER", "Invalid pointer."),
+    0x80004004u32 => ("E_ABORT", "Operation aborted."),
+    0x80004005u32 => ("E_FAIL", "Unspecified error."),
+    0x8000FFFFu32 => ("E_UNEXPECTED", "Catastrophic failure."),
+    0x80010108u32 => ("RPC_E_DISCONNECTED", "The object invoked has disconnected from its clients."),
+    0x80040154u32 => ("REGDB_E_CLASSNOTREG", "Class not registered."),
+    0x800401F0u32 => ("CO_E_NOTINITIALIZED", "CoInitialize has not been called."),
+    0x80070005u32 => ("E_ACCESSDENIED", "General access denied error."),
+    0x80070006u32 => ("E_HANDLE", "Invalid handle."),
+    0x8007000Eu32 => ("E_OUTOFMEMORY", "Ran out of memory."),
+    0x80070057u32 => ("E_INVALIDARG", "One or more arguments are invalid."),
+    0x8007007Eu32 => ("HRESULT_FROM_WIN32(ERROR_MOD_NOT_FOUND)", "The specified module could not be found."),
+    0x800700AAu32 => ("HRESULT_FROM_WIN32(ERROR_BUSY)", "The requested resource is in use."),
+    0x8007045Bu32 => ("HRESULT_FROM_WIN32(ERROR_SHUTDOWN_IN_PROGRESS)", "A system shutdown is in progress."),
+};
+
+static DXGI_D3D12: phf::Map<u32, (&'static str, &'static str)> = phf_map! {
+    0x087A0001u32 => ("DXGI_STATUS_OCCLUDED", "The window content is not visible."),
+    0x087A0002u32 => ("DXGI_STATUS_CLIPPED", "The window is partially clipped."),
+    0x087A0004u32 => ("DXGI_STATUS_NO_REDIRECTION", "No redirection is performed for the swap chain."),
+    0x087A0005u32 => ("DXGI_STATUS_NO_DESKTOP_ACCESS", "The desktop cannot be accessed."),
+    0x087A0006u32 => ("DXGI_STATUS_GRAPHICS_VIDPN_SOURCE_IN_USE", "The video output source is in use by another application."),
+    0x087A0007u32 => ("DXGI_STATUS_MODE_CHANGED", "The display mode changed."),
+    0x087A0008u32 => ("DXGI_STATUS_MODE_CHANGE_IN_PROGRESS", "A display mode change is in progress."),
+    0x887A0001u32 => ("DXGI_ERROR_INVALID_CALL", "The application made a call that is invalid, usually a bad parameter."),
+    0x887A0002u32 => ("DXGI_ERROR_NOT_FOUND", "The object was not found."),
+    0x887A0003u32 => ("DXGI_ERROR_MORE_DATA", "The buffer supplied by the application is not big enough."),
+    0x887A0004u32 => ("DXGI_ERROR_UNSUPPORTED", "The requested functionality is not supported by the device or the driver."),
+    0x887A0005u32 => ("DXGI_ERROR_DEVICE_REMOVED", "The GPU was physically removed, the driver was updated, or the device was lost after a fault."),
+    0x887A0006u32 => ("DXGI_ERROR_DEVICE_HUNG", "The device failed due to a badly formed command, the GPU took too long to execute it."),
+    0x887A0007u32 => ("DXGI_ERROR_DEVICE_RESET", "The device failed due to a badly formed command and was reset."),
+    0x887A000Au32 => ("DXGI_ERROR_WAS_STILL_DRAWING", "The GPU was busy when the operation was requested."),
+    0x887A000Bu32 => ("DXGI_ERROR_FRAME_STATISTICS_DISJOINT", "An event interrupted the gathering of presentation statistics."),
+    0x887A000Cu32 => ("DXGI_ERROR_GRAPHICS_VIDPN_SOURCE_IN_USE", "The output is owned by another application."),
+    0x887A0020u32 => ("DXGI_ERROR_DRIVER_INTERNAL_ERROR", "The driver encountered a problem and was put into the device removed state."),
+    0x887A0021u32 => ("DXGI_ERROR_NONEXCLUSIVE", "A global counter resource is in use."),
+    0x887A0022u32 => ("DXGI_ERROR_NOT_CURRENTLY_AVAILABLE", "The resource or request is not currently available."),
+    0x887A0023u32 => ("DXGI_ERROR_REMOTE_CLIENT_DISCONNECTED", "The remote desktop client disconnected."),
+    0x887A0024u32 => ("DXGI_ERROR_REMOTE_OUTOFMEMORY", "The remote device ran out of memory."),
+    0x887A0025u32 => ("DXGI_ERROR_MODE_CHANGE_IN_PROGRESS", "A mode change is in progress."),
+    0x887A0026u32 => ("DXGI_ERROR_ACCESS_LOST", "The desktop duplication interface is invalid."),
+    0x887A0027u32 => ("DXGI_ERROR_WAIT_TIMEOUT", "The time-out interval elapsed before the resource became available."),
+    0x887A0028u32 => ("DXGI_ERROR_SESSION_DISCONNECTED", "The remote desktop session was disconnected."),
+    0x887A0029u32 => ("DXGI_ERROR_RESTRICT_TO_OUTPUT_STALE", "The output restriction is no longer valid."),
+    0x887A002Au32 => ("DXGI_ERROR_CANNOT_PROTECT_CONTENT", "The content could not be protected."),
+    0x887A002Bu32 => ("DXGI_ERROR_ACCESS_DENIED", "Access to the resource was denied."),
+    0x887A002Cu32 => ("DXGI_ERROR_NAME_ALREADY_EXISTS", "A resource with the given name already exists."),
+    0x887A002Du32 => ("DXGI_ERROR_SDK_COMPONENT_MISSING", "A required SDK component is missing or mismatched."),
+    0x887A002Eu32 => ("DXGI_ERROR_NOT_CURRENT", "The resource is no longer current."),
+    0x887A0030u32 => ("DXGI_ERROR_HW_PROTECTION_OUTOFMEMORY", "Insufficient hardware protected memory."),
+    0x887A0031u32 => ("DXGI_ERROR_DYNAMIC_CODE_POLICY_VIOLATION", "Creating the object would violate the dynamic code policy."),
+    0x887A0032u32 => ("DXGI_ERROR_NON_COMPOSITED_UI", "The operation failed because the compositor is not in control of the output."),
+    0x887A0033u32 => ("DXGI_ERROR_CACHE_CORRUPT", "The shader cache file is corrupted."),
+    0x887A0034u32 => ("DXGI_ERROR_CACHE_FULL", "The shader cache is full."),
+    0x887A0035u32 => ("DXGI_ERROR_CACHE_HASH_COLLISION", "A shader cache entry with a different hash already exists."),
+    0x887A0036u32 => ("DXGI_ERROR_ALREADY_EXISTS", "The desired element already exists."),
+    0x887E0001u32 => ("D3D12_ERROR_ADAPTER_NOT_FOUND", "The cached PSO was created on a different adapter."),
+    0x887E0002u32 => ("D3D12_ERROR_DRIVER_VERSION_MISMATCH", "The cached PSO was created with a different driver version."),
+    0x887E0003u32 => ("D3D12_ERROR_INVALID_REDIST", "The D3D12 SDK version does not match the redistributable in use."),
+};
+
+static WIN32_ERRORS: phf::Map<u32, (&'static str, &'static str)> = phf_map! {
+    0u32 => ("ERROR_SUCCESS", "The operation completed successfully."),
+    1u32 => ("ERROR_INVALID_FUNCTION", "Incorrect function."),
+    2u32 => ("ERROR_FILE_NOT_FOUND", "The system cannot find the file specified."),
+    3u32 => ("ERROR_PATH_NOT_FOUND", "The system cannot find the path specified."),
+    4u32 => ("ERROR_TOO_MANY_OPEN_FILES", "The system cannot open the file."),
+    5u32 => ("ERROR_ACCESS_DENIED", "Access is denied."),
+    6u32 => ("ERROR_INVALID_HANDLE", "The handle is invalid."),
+    8u32 => ("ERROR_NOT_ENOUGH_MEMORY", "Not enough memory resources are available to process this command."),
+    13u32 => ("ERROR_INVALID_DATA", "The data is invalid."),
+    14u32 => ("ERROR_OUTOFMEMORY", "Not enough memory resources are available to complete this operation."),
+    21u32 => ("ERROR_NOT_READY", "The device is not ready."),
+    23u32 => ("ERROR_CRC", "Data error (cyclic redundancy check)."),
+    31u32 => ("ERROR_GEN_FAILURE", "A device attached to the system is not functioning."),
+    32u32 => ("ERROR_SHARING_VIOLATION", "The process cannot access the file because it is being used by another process."),
+    33u32 => ("ERROR_LOCK_VIOLATION", "The process cannot access the file because another process has locked a portion of the file."),
+    38u32 => ("ERROR_HANDLE_EOF", "Reached the end of the file."),
+    50u32 => ("ERROR_NOT_SUPPORTED", "The request is not supported."),
+    87u32 => ("ERROR_INVALID_PARAMETER", "The parameter is incorrect."),
+    112u32 => ("ERROR_DISK_FULL", "There is not enough space on the disk."),
+    122u32 => ("ERROR_INSUFFICIENT_BUFFER", "The data area passed to a system call is too small."),
+    126u32 => ("ERROR_MOD_NOT_FOUND", "The specified module could not be found."),
+    127u32 => ("ERROR_PROC_NOT_FOUND", "The specified procedure could not be found."),
+    170u32 => ("ERROR_BUSY", "The requested resource is in use."),
+    183u32 => ("ERROR_ALREADY_EXISTS", "Cannot create a file when that file already exists."),
+    193u32 => ("ERROR_BAD_EXE_FORMAT", "The file is not a valid Win32 application."),
+    258u32 => ("WAIT_TIMEOUT", "The wait operation timed out."),
+    487u32 => ("ERROR_INVALID_ADDRESS", "Attempt to access invalid address."),
+    998u32 => ("ERROR_NOACCESS", "Invalid access to memory location."),
+    1114u32 => ("ERROR_DLL_INIT_FAILED", "A dynamic link library (DLL) initialization routine failed."),
+    1115u32 => ("ERROR_SHUTDOWN_IN_PROGRESS", "A system shutdown is in progress."),
+    1167u32 => ("ERROR_DEVICE_NOT_CONNECTED", "The device is not connected."),
+    1450u32 => ("ERROR_NO_SYSTEM_RESOURCES", "Insufficient system resources exist to complete the requested service."),
+    1455u32 => ("ERROR_COMMITMENT_LIMIT", "The paging file is too small for this operation to complete."),
+    1460u32 => ("ERROR_TIMEOUT", "This operation returned because the timeout period expired."),
+    1785u32 => ("ERROR_UNRECOGNIZED_MEDIA", "The disk media is not recognized."),
+    6005u32 => ("ERROR_EFS_DISABLED", "Encryption has been disabled for this system."),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_parsed_as_hex_or_decimal() {
+        assert_eq!(parse_code("0xC0000005"), Some(0xC0000005));
+        assert_eq!(parse_code(" 0Xc0000005 "), Some(0xC0000005));
+        assert_eq!(parse_code("5"), Some(5));
+        //negative HRESULTs as printed by some tools
+        assert_eq!(parse_code("-2147024891"), Some(0x80070005));
+        assert_eq!(parse_code("0xZZ"), None);
+        assert_eq!(parse_code("access violation"), None);
+    }
+
+    #[test]
+    fn known_codes_are_guessed_from_their_table() {
+        let access_violation = DecodedCode::guess(0xC0000005);
+        assert_eq!(access_violation.kind, CodeKind::NtStatus);
+        assert_eq!(access_violation.name(), Some("STATUS_ACCESS_VIOLATION"));
+        assert_eq!(access_violation.severity, Severity::Error);
+
+        let device_removed = DecodedCode::guess(0x887A0005);
+        assert_eq!(device_removed.kind, CodeKind::HResult);
+        assert_eq!(device_removed.name(), Some("DXGI_ERROR_DEVICE_REMOVED"));
+        assert_eq!(device_removed.facility_name, Some("FACILITY_DXGI"));
+
+        assert_eq!(DecodedCode::guess(5).name(), Some("ERROR_ACCESS_DENIED"));
+    }
+
+    #[test]
+    fn unknown_codes_are_guessed_from_their_layout() {
+        assert_eq!(DecodedCode::guess(0x1234).kind, CodeKind::Win32);
+        assert_eq!(DecodedCode::guess(0x887A1234).kind, CodeKind::HResult);
+        let unknown = DecodedCode::guess(0xC0FF1234);
+        assert_eq!(unknown.kind, CodeKind::NtStatus);
+        assert_eq!(unknown.name(), None);
+        assert_eq!(unknown.code_value, 0x1234);
+    }
+
+    #[test]
+    fn wrapped_win32_errors_are_named() {
+        let lines = DecodedCode::new(0x80070005, CodeKind::HResult).lines();
+        assert!(lines.contains(&"wraps Win32 error 5 (ERROR_ACCESS_DENIED)".to_string()), "{lines:?}");
+    }
+}
@@ -0,0 +1,246 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const GPU_VENDORS: [(&str, &str); 5] = [
+    ("nvidia", "NVIDIA"),
+    ("geforce", "NVIDIA"),
+    ("amd", "AMD"),
+    ("radeon", "AMD"),
+    ("intel", "Intel"),
+];
+
+//memory keys with one of these words are about the gpu, e.g. "GPU Memory" or "Dedicated video memory"
+const GRAPHICS_WORDS: [&str; 7] = ["gpu", "video", "graphics", "display", "adapter", "dedicated", "shared"];
+//memory keys that aren't the installed ram, e.g. "Available Physical Memory"
+const PARTIAL_MEMORY_WORDS: [&str; 6] = ["available", "free", "used", "virtual", "page", "commit"];
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SystemInfo {
+    pub cpu: Option<String>,
+    pub cpu_cores: Option<u32>,
+    pub cpu_threads: Option<u32>,
+    pub ram_mb: Option<u64>,
+    pub gpus: Vec<String>,
+    pub vram_mb: Option<u64>,
+    pub driver_version: Option<String>,
+    pub resolution: Option<(u32, u32)>,
+    //every line that could not be mapped onto one of the fields above
+    pub other: Vec<(String, String)>,
+}
+
+impl SystemInfo {
+    pub fn parse(system_info: &str) -> SystemInfo {
+        let mut info = SystemInfo::default();
+        let system_info = system_info.replace("System Info:", "");
+
+        for line in system_info.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (key, value) = match line.split_once(':').or_else(|| line.split_once('=')) {
+                Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                None => (String::new(), line.to_string()),
+            };
+            let lower_key = key.to_lowercase();
+            let words: Vec<&str> = lower_key.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+            let is_memory = words.contains(&"ram") || words.contains(&"memory");
+
+            if lower_key.contains("logical") || lower_key.contains("thread") {
+                info.cpu_threads = parse_count(&value).or(info.cpu_threads);
+            } else if lower_key.contains("core") {
+                info.cpu_cores = parse_count(&value).or(info.cpu_cores);
+            } else if lower_key.contains("cpu") || lower_key.contains("processor") {
+                info.cpu_cores = info.cpu_cores.or_else(|| count_suffixed(&value, &["cores", "core", "c/"]));
+                info.cpu_threads = info.cpu_threads.or_else(|| count_suffixed(&value, &["threads", "thread", "t)"]));
+                info.cpu = Some(value);
+            } else if lower_key.contains("vram") || words.contains(&"dedicated") || (is_memory && words.iter().any(|word| GRAPHICS_WORDS.contains(word))) {
+                //shared and dedicated system memory are system ram the gpu may use, neither vram nor the installed ram
+                if words.contains(&"shared") || words.contains(&"system") {
+                    info.other.push((key, value));
+                } else {
+                    info.vram_mb = parse_size_mb(&value).or(info.vram_mb);
+                }
+            } else if is_memory {
+                if words.iter().any(|word| PARTIAL_MEMORY_WORDS.contains(word)) {
+                    info.other.push((key, value));
+                } else {
+                    info.ram_mb = parse_size_mb(&value).or(info.ram_mb);
+                }
+            } else if lower_key.contains("driver") {
+                info.driver_version = Some(value);
+            } else if lower_key.contains("gpu") || lower_key.contains("adapter") || lower_key.contains("graphics") {
+                if info.vram_mb.is_none() {
+                    //ascii lowercase keeps the byte offsets of the original value
+                    info.vram_mb = value.to_ascii_lowercase().find("vram").and_then(|i| parse_size_mb(&value[i + 4..]));
+                }
+                info.gpus.push(value);
+            } else if lower_key.contains("resolution") || lower_key.contains("display") {
+                match parse_resolution(&value) {
+                    Some(resolution) => info.resolution = Some(resolution),
+                    None => info.other.push((key, value)),
+                }
+            } else {
+                info.other.push((key, value));
+            }
+        }
+        info
+    }
+
+    pub fn gpu_vendor(&self) -> Option<&'static str> {
+        let gpu = self.gpus.first()?.to_lowercase();
+        GPU_VENDORS.iter().find(|(pattern, _)| gpu.contains(pattern)).map(|(_, vendor)| *vendor)
+    }
+
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = vec![];
+        if let Some(cpu) = &self.cpu {
+            rows.push(("CPU".to_string(), cpu.clone()));
+        }
+        match (self.cpu_cores, self.cpu_threads) {
+            (Some(cores), Some(threads)) => rows.push(("Cores".to_string(), format!("{cores} cores / {threads} threads"))),
+            (Some(cores), None) => rows.push(("Cores".to_string(), format!("{cores} cores"))),
+            (None, Some(threads)) => rows.push(("Cores".to_string(), format!("{threads} threads"))),
+            (None, None) => {}
+        }
+        if let Some(ram) = self.ram_mb {
+            rows.push(("RAM".to_string(), format_size_mb(ram)));
+        }
+        for gpu in &self.gpus {
+            rows.push(("GPU".to_string(), gpu.clone()));
+        }
+        if let Some(vram) = self.vram_mb {
+            rows.push(("VRAM".to_string(), format_size_mb(vram)));
+        }
+        if let Some(driver) = &self.driver_version {
+            rows.push(("Driver".to_string(), driver.clone()));
+        }
+        if let Some((width, height)) = self.resolution {
+            rows.push(("Resolution".to_string(), format!("{width}x{height}")));
+        }
+        rows
+    }
+}
+
+//the gpu entries end with the vram, which is already implied by the model
+pub fn gpu_model(gpu: &str) -> &str {
+    gpu.split(" (").next().unwrap_or(gpu).trim()
+}
+
+pub fn format_size_mb(mb: u64) -> String {
+    if mb >= 1024 {
+        format!("{:.1} GB", mb as f64 / 1024.0)
+    } else {
+        format!("{mb} MB")
+    }
+}
+
+pub fn first_number(value: &str) -> Option<f64> {
+    let start = value.find(|c: char| c.is_ascii_digit())?;
+    let number: String = value[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    number.parse::<f64>().ok()
+}
+
+fn parse_count(value: &str) -> Option<u32> {
+    first_number(value).map(|n| n as u32)
+}
+
+//finds a number directly followed by one of the suffixes, e.g. "8 cores" or "(8C/16T)"
+fn count_suffixed(value: &str, suffixes: &[&str]) -> Option<u32> {
+    let lower = value.to_lowercase();
+    for suffix in suffixes {
+        if let Some(end) = lower.find(suffix) {
+            let digits: String = lower[..end].trim_end().chars().rev().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(count) = digits.chars().rev().collect::<String>().parse::<u32>() {
+                return Some(count);
+            }
+        }
+    }
+    None
+}
+
+//sizes without a unit are assumed to be in bytes when large and in MB otherwise
+pub fn parse_size_mb(value: &str) -> Option<u64> {
+    let number = first_number(value)?;
+    let lower = value.to_lowercase();
+    let mb = if lower.contains("tb") {
+        number * 1024.0 * 1024.0
+    } else if lower.contains("gb") || lower.contains("gib") {
+        number * 1024.0
+    } else if lower.contains("mb") || lower.contains("mib") {
+        number
+    } else if lower.contains("kb") || lower.contains("kib") {
+        number / 1024.0
+    } else if number > 1024.0 * 1024.0 {
+        number / (1024.0 * 1024.0)
+    } else {
+        number
+    };
+    Some(mb.round() as u64)
+}
+
+pub fn parse_resolution(value: &str) -> Option<(u32, u32)> {
+    let lower = value.to_lowercase();
+    let (width, height) = lower.split_once('x')?;
+    let width: String = width.trim_end().chars().rev().take_while(|c| c.is_ascii_digit()).collect::<Vec<_>>().into_iter().rev().collect();
+    let height: String = height.trim_start().chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //the layout the game writes
+    const GAME_SAMPLE: &str = "System Info:\nCPU: AMD Ryzen 7 5800X 8-Core Processor (8 cores)\nLogical processors: 16\nRAM: 32768 MB\nGPU: NVIDIA GeForce RTX 3080 (VRAM: 10240 MB)\nDriver Version: 531.41\nResolution: 2560x1440";
+    //dxdiag style keys, where several memory lines belong to the gpu
+    const DXDIAG_SAMPLE: &str = "Processor: Intel(R) Core(TM) i7-9700K CPU @ 3.60GHz (8 CPUs), ~3.6GHz\nMemory: 16384MB RAM\nAvailable OS Memory: 16310MB RAM\nPage File: 9000MB used, 12000MB available\nCard name: NVIDIA GeForce GTX 1660 SUPER\nDisplay Memory: 14070 MB\nDedicated Memory: 5991 MB\nShared Memory: 8079 MB\nDriver Version: 31.0.15.3623";
+
+    #[test]
+    fn parses_the_game_layout() {
+        let info = SystemInfo::parse(GAME_SAMPLE);
+        assert_eq!(info.cpu.as_deref(), Some("AMD Ryzen 7 5800X 8-Core Processor (8 cores)"));
+        assert_eq!(info.cpu_cores, Some(8));
+        assert_eq!(info.cpu_threads, Some(16));
+        assert_eq!(info.ram_mb, Some(32768));
+        assert_eq!(info.gpus, vec!["NVIDIA GeForce RTX 3080 (VRAM: 10240 MB)"]);
+        assert_eq!(info.vram_mb, Some(10240));
+        assert_eq!(info.driver_version.as_deref(), Some("531.41"));
+        assert_eq!(info.resolution, Some((2560, 1440)));
+        assert_eq!(info.gpu_vendor(), Some("NVIDIA"));
+    }
+
+    #[test]
+    fn gpu_memory_keys_are_not_ram() {
+        let info = SystemInfo::parse(DXDIAG_SAMPLE);
+        assert_eq!(info.ram_mb, Some(16384));
+        assert_eq!(info.vram_mb, Some(5991));
+
+        let info = SystemInfo::parse("RAM: 8192 MB\nGPU Memory: 4096 MB\nShared memory: 4096 MB");
+        assert_eq!(info.ram_mb, Some(8192));
+        assert_eq!(info.vram_mb, Some(4096));
+
+        let info = SystemInfo::parse("Dedicated video memory: 8 GB\nTotal physical memory: 16 GB");
+        assert_eq!(info.ram_mb, Some(16384));
+        assert_eq!(info.vram_mb, Some(8192));
+    }
+
+    #[test]
+    fn ram_is_matched_on_whole_words() {
+        let info = SystemInfo::parse("Program: HITMAN3.exe 2048\nFrame rate: 60\nParameters: 4096");
+        assert_eq!(info.ram_mb, None);
+    }
+
+    #[test]
+    fn vram_in_a_non_ascii_gpu_name() {
+        //'İ' lowercases to more bytes than it has, which used to shift the vram offset
+        let info = SystemInfo::parse("GPU: İntel Arc A770 (VRAM: 16384 MB)");
+        assert_eq!(info.vram_mb, Some(16384));
+    }
+
+    #[test]
+    fn sizes_and_models() {
+        assert_eq!(parse_size_mb("16 GB"), Some(16384));
+        assert_eq!(parse_size_mb("17179869184"), Some(16384));
+        assert_eq!(parse_size_mb("512 MB"), Some(512));
+        assert_eq!(gpu_model("NVIDIA GeForce RTX 3080 (VRAM: 10240 MB)"), "NVIDIA GeForce RTX 3080");
+        assert_eq!(format_size_mb(16384), "16.0 GB");
+        assert_eq!(format_size_mb(512), "512 MB");
+    }
+}
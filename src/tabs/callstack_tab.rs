@@ -7,7 +7,9 @@ use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, List, ListItem};
 use crate::app::DataStore;
+use crate::callstack::parse_callstack;
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::modules::parse_modules;
 use crate::tabs::tab::Tab;
 
 pub struct CallstackTab {
@@ -54,32 +56,15 @@ impl Tab for CallstackTab {
 
 fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, app_state: &mut DataStore, area: Rect)
 {
-    let callstack = metrics.callstack.to_string();
-    let mut callstack: Vec<String> = callstack.split(';').map(|x| x.to_string()).collect();
+    let modules = parse_modules(&metrics.modules.to_string());
+    let frames = parse_callstack(&metrics.callstack.to_string(), &modules);
 
-    let modules = metrics.modules.to_string();
-    let modules: Vec<String> = modules.split(';').map(|x| x.to_string()).collect();
-
-
-    for call in callstack.iter_mut() {
-        if let Ok(address) = u64::from_str_radix(call, 16) {
-            let mut dist = u64::MAX;
-            let mut module_name = "unknown";
-            for module in modules.chunks(5) {
-                if let Ok(module_address) = u64::from_str_radix(module[1].as_str(), 16) {
-                    if let Some(diff) = address.checked_sub(module_address) {
-                        if diff < dist {
-                            dist = diff;
-                            module_name = module[0].as_str();
-                        }
-                    }
-                }
-            }
-            *call = format!("0x{}  (in {})", *call, module_name);
-        }
-    }
-
-    app_state.callstack.items = callstack;
+    app_state.callstack.items = frames.iter()
+        .map(|frame| match frame.module {
+            Some(_) => format!("0x{:X}  (in {})", frame.address, frame.location()),
+            None => format!("0x{:X}  (in unknown)", frame.address),
+        })
+        .collect();
 
     let items: Vec<ListItem> = app_state.callstack
         .items
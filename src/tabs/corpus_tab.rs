@@ -0,0 +1,316 @@
+use std::io::Stdout;
+use crossterm::event::KeyCode;
+use native_dialog::FileDialog;
+use tui::backend::CrosstermBackend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use unicode_width::UnicodeWidthChar;
+use crate::app::DataStore;
+use crate::controls::stateful_list::StatefulList;
+use crate::corpus::{Corpus, CorpusEntry, Facet};
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::tabs::tab::Tab;
+
+//widths of the time, build, scene and exception columns, the signature gets the rest
+const COLUMN_WIDTHS: [usize; 4] = [19, 24, 34, 36];
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortColumn {
+    Time,
+    Build,
+    Scene,
+    Exception,
+    Signature,
+}
+
+impl SortColumn {
+    fn next(self) -> SortColumn {
+        match self {
+            SortColumn::Time => SortColumn::Build,
+            SortColumn::Build => SortColumn::Scene,
+            SortColumn::Scene => SortColumn::Exception,
+            SortColumn::Exception => SortColumn::Signature,
+            SortColumn::Signature => SortColumn::Time,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortColumn::Time => "time",
+            SortColumn::Build => "build",
+            SortColumn::Scene => "scene",
+            SortColumn::Exception => "exception",
+            SortColumn::Signature => "signature",
+        }
+    }
+
+    fn key(self, entry: &CorpusEntry) -> &str {
+        match self {
+            SortColumn::Time => &entry.time,
+            SortColumn::Build => &entry.build,
+            SortColumn::Scene => &entry.scene,
+            SortColumn::Exception => &entry.exception,
+            SortColumn::Signature => &entry.signature,
+        }
+    }
+}
+
+pub struct CorpusTab {
+    pub title: String,
+    //the entries left after filtering and sorting
+    list: StatefulList<CorpusEntry>,
+    sort: SortColumn,
+    descending: bool,
+    query: String,
+    searching: bool,
+    //set from the statistics tab
+    facet: Option<Facet>,
+    message: Option<String>,
+    //corpus generation the list was built from
+    generation: u64,
+}
+
+impl CorpusTab {
+    pub fn new() -> CorpusTab {
+        CorpusTab {
+            title: "Corpus".to_string(),
+            list: StatefulList::with_items(vec![]),
+            sort: SortColumn::Time,
+            descending: true,
+            query: String::new(),
+            searching: false,
+            facet: None,
+            message: None,
+            generation: 0,
+        }
+    }
+
+    fn choose_folder(&mut self, app_data: &mut DataStore) {
+        let path = FileDialog::new()
+            .set_location("~/Documents")
+            .show_open_single_dir();
+
+        match path {
+            Ok(Some(path)) => {
+                app_data.corpus.open(path, &app_data.scenes);
+                self.update_list(app_data);
+            }
+            Ok(None) => {}
+            Err(e) => self.message = Some(format!("Failed to choose a folder: {e}")),
+        }
+    }
+
+    fn update_list(&mut self, app_data: &DataStore) {
+        let query = self.query.to_lowercase();
+        let mut visible: Vec<CorpusEntry> = app_data.corpus.entries.iter()
+            .filter(|entry| self.facet.as_ref().map(|facet| facet.matches(entry)).unwrap_or(true))
+            .filter(|entry| query.is_empty()
+                || [&entry.time, &entry.build, &entry.scene, &entry.exception, &entry.signature]
+                    .iter().any(|value| value.to_lowercase().contains(&query)))
+            .cloned()
+            .collect();
+        visible.sort_by(|a, b| self.sort.key(a).cmp(self.sort.key(b)));
+        if self.descending {
+            visible.reverse();
+        }
+
+        //keep the selected crash selected while the list changes around it
+        let selected = self.list.state.selected().and_then(|i| self.list.items.get(i)).map(|entry| entry.origin.clone());
+        let index = selected.and_then(|origin| visible.iter().position(|entry| entry.origin == origin));
+        self.list.items = visible;
+        let index = match (index, self.list.state.selected()) {
+            (Some(index), _) => index,
+            (None, Some(i)) => i.min(self.list.items.len().saturating_sub(1)),
+            (None, None) => 0,
+        };
+        self.list.state.select(if self.list.items.is_empty() { None } else { Some(index) });
+        self.generation = app_data.corpus.generation;
+    }
+}
+
+impl Tab for CorpusTab {
+    fn on_load(&mut self, app_data: &mut DataStore) {
+        self.update_list(app_data);
+    }
+
+    fn on_key(&mut self, app_data: &mut DataStore, key: KeyCode) {
+        if self.searching {
+            match key {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => { self.query.pop(); }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                _ => {}
+            }
+            self.update_list(app_data);
+            return;
+        }
+
+        match key {
+            KeyCode::Up if !self.list.items.is_empty() => self.list.previous(),
+            KeyCode::Down if !self.list.items.is_empty() => self.list.next(),
+            KeyCode::Enter => {
+                if let Some(entry) = self.list.state.selected().and_then(|i| self.list.items.get(i)) {
+                    app_data.open_request = Some(entry.origin.clone());
+                }
+            }
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('o') => {
+                self.sort = self.sort.next();
+                self.update_list(app_data);
+            }
+            KeyCode::Char('v') => {
+                self.descending = !self.descending;
+                self.update_list(app_data);
+            }
+            KeyCode::Char('x') => {
+                self.facet = None;
+                self.update_list(app_data);
+            }
+            KeyCode::Char('f') => self.choose_folder(app_data),
+            _ => {}
+        }
+    }
+
+    fn get_title(&self) -> &String {
+        &self.title
+    }
+
+    fn captures_input(&self) -> bool {
+        self.searching
+    }
+
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, _metrics: &mut G2CrashMetrics, area: Rect)
+    {
+        if let Some(facet) = app_data.corpus_facet.take() {
+            self.facet = Some(facet);
+            self.list.state.select(None);
+            self.update_list(app_data);
+        } else if self.generation != app_data.corpus.generation {
+            self.update_list(app_data);
+        }
+
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(4),
+                    Constraint::Min(0),
+                ].as_ref(),
+            )
+            .split(area);
+
+        draw_status(f, self, &app_data.corpus, chunks[0]);
+        draw_crashes(f, self, chunks[1]);
+    }
+}
+
+fn draw_status(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &CorpusTab, corpus: &Corpus, area: Rect)
+{
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let key = Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD);
+    let search_style = if tab.searching { bold.fg(Color::Yellow) } else { bold };
+    let status = vec![
+        Span::styled("/", key),
+        Span::raw(" filter: "),
+        Span::styled(format!("{}{}", tab.query, if tab.searching { "_" } else { "" }), search_style),
+        Span::raw("   s"),
+        Span::styled("o", key),
+        Span::raw("rt: "),
+        Span::styled(format!("{} {}", tab.sort.name(), if tab.descending { "descending" } else { "ascending" }), bold),
+        Span::raw(" (re"),
+        Span::styled("v", key),
+        Span::raw("erse)   "),
+        Span::styled("f", key),
+        Span::raw("older   "),
+        Span::styled("Enter", key),
+        Span::raw(" opens the crash"),
+    ];
+    let mut text = Text::from(Spans::from(status));
+    let summary = match &corpus.source {
+        Some(source) => {
+            let mut summary = format!(" {} of {} crashes in {}", tab.list.items.len(), corpus.entries.len(), source.display());
+            if corpus.failures > 0 {
+                summary.push_str(&format!(", {} unreadable files", corpus.failures));
+            }
+            summary
+        }
+        None => " No corpus loaded, press f to choose a folder or start with --corpus <folder or crash database>".to_string(),
+    };
+    let mut summary = vec![Span::raw(summary)];
+    if let Some(facet) = &tab.facet {
+        summary.push(Span::raw(" - only "));
+        summary.push(Span::styled(facet.to_string(), bold.fg(Color::Yellow)));
+        summary.push(Span::raw(" ("));
+        summary.push(Span::styled("x", key));
+        summary.push(Span::raw(" clears)"));
+    }
+    if let Some(message) = corpus.error.as_ref().or(tab.message.as_ref()) {
+        summary.push(Span::raw(format!(" - {message}")));
+    }
+    text.extend(Text::from(Spans::from(summary)));
+
+    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+}
+
+fn draw_crashes(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &mut CorpusTab, area: Rect)
+{
+    let block = Block::default().borders(Borders::ALL).title("Crashes");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ].as_ref(),
+        )
+        .split(inner);
+
+    let header = row_text(["Time", "Build", "Scene", "Exception", "Signature"]);
+    f.render_widget(Paragraph::new(Span::styled(format!("  {header}"), Style::default().add_modifier(Modifier::BOLD))), chunks[0]);
+
+    let items: Vec<ListItem> = tab.list.items.iter()
+        .map(|entry| ListItem::new(row_text([&entry.time, &entry.build, &entry.scene, &entry.exception, &entry.signature])))
+        .collect();
+    let list = List::new(items)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(list, chunks[1], &mut tab.list.state);
+}
+
+fn row_text(values: [&str; 5]) -> String {
+    let mut text = String::new();
+    for (value, width) in values.iter().zip(COLUMN_WIDTHS) {
+        text.push_str(&fit(value, width));
+        text.push(' ');
+    }
+    text.push_str(values[4]);
+    text
+}
+
+//pads or cuts the text to exactly the given terminal width
+fn fit(text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if used + c_width > width {
+            fitted.pop();
+            fitted.push('…');
+            break;
+        }
+        fitted.push(c);
+        used += c_width;
+    }
+    let used: usize = fitted.chars().map(|c| c.width().unwrap_or(0)).sum();
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
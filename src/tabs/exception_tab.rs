@@ -3,11 +3,14 @@ use crossterm::event::KeyCode;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::text::Text;
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans, Text};
 use tui::widgets::{Block, Borders, Paragraph};
 use crate::app::DataStore;
+use crate::exception_record::{decode_flags, ExceptionDetails};
 use crate::g2_crash_metrics::{G2CrashMetrics, G2Exception};
-use crate::nt_status_enum::NTSTATUS;
+use crate::modules::{find_module, parse_modules};
+use crate::status_codes::{CodeKind, DecodedCode};
 
 use crate::tabs::tab::Tab;
 
@@ -32,7 +35,7 @@ impl Tab for ExceptionTab {
         &self.title
     }
 
-    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, _app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
     {
         let chunks = Layout::default().direction(Direction::Horizontal)
             .constraints(
@@ -41,43 +44,40 @@ impl Tab for ExceptionTab {
                 ].as_ref(),
             )
             .split(area);
-        draw_info(f, metrics, chunks[0]);
+        draw_info(f, app_data, metrics, chunks[0]);
     }
 }
 
-fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect)
+fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &DataStore, metrics: &mut G2CrashMetrics, area: Rect)
 {
-    let modules = metrics.modules.to_string();
-    let modules: Vec<String> = modules.split(';').map(|x| x.to_string()).collect();
+    let modules = parse_modules(&metrics.modules.to_string());
 
     let exception: G2Exception = metrics.exception;
 
     let mut text = Text::raw("\n");
-    text.extend(Text::raw(format!(" flag: 0x{:x}", exception.exception_flags)));
-    text.extend(Text::raw("\n"));
-    if let Some(nt_status) = NTSTATUS.get(&exception.exception_code) {
-        text.extend(Text::raw(format!(" code: 0x{:x} ({})", exception.exception_code, nt_status)));
+    let flags = decode_flags(exception.exception_flags);
+    if flags.is_empty() {
+        text.extend(Text::raw(format!(" flag: 0x{:x}", exception.exception_flags)));
     } else {
-        text.extend(Text::raw(format!(" code: 0x{:x}", exception.exception_code)));
+        text.extend(Text::raw(format!(" flag: 0x{:x} ({})", exception.exception_flags, flags.join(" | "))));
+    }
+    text.extend(Text::raw("\n"));
+    let code = DecodedCode::new(exception.exception_code, CodeKind::NtStatus);
+    text.extend(Text::raw(format!(" code: {code}")));
+    if let Some(description) = code.description() {
+        text.extend(Text::raw(format!("  {description}")));
     }
+    text.extend(Text::raw(format!("  severity: {}, customer: {}, facility: 0x{:x} ({})",
+                                  code.severity, code.customer, code.facility, code.facility_name.unwrap_or("unknown"))));
 
 
     text.extend(Text::raw("\n"));
 
     let address = exception.exception_address;
-    let mut dist = u64::MAX;
-    let mut module_name = "unknown";
-    for module in modules.chunks(5) {
-        if let Ok(module_address) = u64::from_str_radix(module[1].as_str(), 16) {
-            if let Some(diff) = address.checked_sub(module_address) {
-                if diff < dist {
-                    dist = diff;
-                    module_name = module[0].as_str();
-                }
-            }
-        }
+    match find_module(&modules, address) {
+        Some((module, offset)) => text.extend(Text::raw(format!(" address: 0x{address:x}  (in {}+0x{offset:x})", module.name))),
+        None => text.extend(Text::raw(format!(" address: 0x{address:x}  (in unknown)"))),
     }
-    text.extend(Text::raw(format!(" address: 0x{address:x}  (in {module_name})")));
     text.extend(Text::raw("\n"));
 
     text.extend(Text::raw(format!(" num parameters: {}", exception.exception_num_parameters)));
@@ -89,6 +89,22 @@ fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetri
     text.extend(Text::raw(format!("  3. 0x{:x}", exception.exception_information_03)));
     text.extend(Text::raw("\n"));
 
+    let details = ExceptionDetails::new(&exception, &modules).lines();
+    if !details.is_empty() {
+        text.extend(Text::from(Spans::from(Span::styled(" Interpretation: ", Style::default().add_modifier(Modifier::BOLD)))));
+        for line in details {
+            text.extend(Text::raw(format!("  {line}")));
+        }
+        text.extend(Text::raw("\n"));
+    }
+
+    if !app_data.diagnoses.is_empty() {
+        text.extend(Text::from(Spans::from(Span::styled(" Diagnosis: ", Style::default().add_modifier(Modifier::BOLD)))));
+        for rule in &app_data.diagnoses {
+            text.extend(Text::raw(format!("  {}: {}", rule.name, rule.diagnosis)));
+        }
+        text.extend(Text::raw("\n"));
+    }
 
     let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Exception"));
     f.render_widget(paragraph, area);
@@ -1,39 +1,74 @@
 use std::io::Stdout;
-use cgmath::{Deg, Euler, Matrix3, Quaternion, Rad};
 use crossterm::event::KeyCode;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans, Text};
+use tui::symbols::Marker;
 use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
+use tui::widgets::canvas::{Canvas, Line, Points, Rectangle};
 use crate::app::DataStore;
+use crate::camera::{CameraFormat, CameraTransform};
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::online::OnlineInfo;
+use crate::runtime_id::{format_id, runtime_id, scene_resource_paths};
 use crate::tabs::tab::Tab;
+use crate::timing::{format_time, CrashTiming};
+
+//half the width of the minimap in world units when the location has no map data
+const MINIMAP_RANGE: f64 = 50.0;
 
 pub struct GameplayTab {
     pub title: String,
+    //parsed during draw so key handlers can export it
+    camera: Option<CameraTransform>,
+    message: Option<String>,
 }
 
 impl GameplayTab {
     pub fn new() -> GameplayTab {
         GameplayTab {
             title: "Gameplay".to_string(),
+            camera: None,
+            message: None,
         }
     }
+
+    fn copy_camera(&mut self, format: CameraFormat) {
+        let camera = match self.camera {
+            Some(camera) => camera,
+            None => {
+                self.message = Some("No valid camera transform to copy".to_string());
+                return;
+            }
+        };
+        let copied = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(camera.export(format)));
+        self.message = Some(match copied {
+            Ok(()) => format!("Copied camera as {}", format.name()),
+            Err(e) => format!("Failed to copy camera: {e}"),
+        });
+    }
 }
 
 
 impl Tab for GameplayTab {
     fn on_load(&mut self, _app_data: &mut DataStore) {}
 
-    fn on_key(&mut self, _app_data: &mut DataStore, _key: KeyCode) {}
+    fn on_key(&mut self, _app_data: &mut DataStore, key: KeyCode) {
+        match key {
+            KeyCode::Char('m') => self.copy_camera(CameraFormat::SMatrix43),
+            KeyCode::Char('k') => self.copy_camera(CameraFormat::QuickEntity),
+            KeyCode::Char('t') => self.copy_camera(CameraFormat::Teleport),
+            _ => {}
+        }
+    }
 
     fn get_title(&self) -> &String {
         &self.title
     }
 
-    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, _app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
     {
         let chunks = Layout::default().direction(Direction::Horizontal)
             .constraints(
@@ -43,7 +78,17 @@ impl Tab for GameplayTab {
                 ].as_ref(),
             )
             .split(area);
-        draw_info(f, metrics, chunks[0]);
+        let chunks_left = Layout::default().direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(60),
+                ].as_ref(),
+            )
+            .split(chunks[0]);
+
+        draw_info(f, metrics, app_data, chunks_left[0]);
+        draw_minimap(f, metrics, app_data, chunks_left[1]);
 
         let chunks_right = Layout::default().direction(Direction::Vertical)
             .constraints(
@@ -54,70 +99,98 @@ impl Tab for GameplayTab {
             )
             .split(chunks[1]);
 
-        draw_camera(f, metrics, chunks_right[0]);
+        let camera_chunks = Layout::default().direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+            .split(chunks_right[0]);
+        self.camera = draw_camera(f, metrics, camera_chunks[0]);
+        let hint = self.message.clone()
+            .unwrap_or_else(|| "copy camera: m SMatrix43, k QuickEntity, t teleport".to_string());
+        let paragraph = Paragraph::new(hint).block(Block::default().borders(Borders::ALL));
+        f.render_widget(paragraph, camera_chunks[1]);
         draw_online(f, metrics, chunks_right[1]);
     }
 }
 
 fn draw_online(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect) {
-    let net_role = metrics.net_role.to_string();
+    let server_version = metrics.online_server_version.to_string();
+    let online = OnlineInfo::parse(&metrics.net_role.to_string(), &server_version);
+
+    let net_role = online.role.to_string();
     let net_role_tag = vec![
         Span::raw("Role: "),
         Span::styled(&net_role, Style::default().add_modifier(Modifier::BOLD)),
     ];
 
-    let server_version = metrics.online_server_version.to_string();
     let server_version_tag = vec![
         Span::raw("server version: "),
         Span::styled(&server_version, Style::default().add_modifier(Modifier::BOLD)),
     ];
 
+    let server_kind = online.server_kind.to_string();
+    let server_style = if online.server_kind.is_private() {
+        Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    };
+    let server_kind_tag = vec![
+        Span::raw("server: "),
+        Span::styled(&server_kind, server_style),
+    ];
 
     let mut text = Text::raw("\n");
     text.extend(Text::from(Spans::from(net_role_tag)));
     text.extend(Text::raw("\n"));
     text.extend(Text::from(Spans::from(server_version_tag)));
+    if let Some(version) = &online.server_version {
+        let mut components = format!("  major {}, minor {}", version.major, version.minor);
+        if let Some(patch) = version.patch {
+            components.push_str(&format!(", patch {patch}"));
+        }
+        if !version.suffix.is_empty() {
+            components.push_str(&format!(", build {}", version.suffix));
+        }
+        text.extend(Text::styled(components, Style::default().fg(Color::DarkGray)));
+    }
+    text.extend(Text::from(Spans::from(server_kind_tag)));
+    if online.server_kind.is_private() {
+        text.extend(Text::raw("\n"));
+        text.extend(Text::styled("Not an official server: online behaviour may come from the private server.", Style::default().fg(Color::Yellow)));
+    }
     text.extend(Text::raw("\n"));
 
     let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("online"));
     f.render_widget(paragraph, area);
 }
 
-fn draw_camera(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect)
+fn draw_camera(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect) -> Option<CameraTransform>
 {
-    let camera_str = metrics.camera.to_string();
-    let test = camera_str.split(',');
-    let camera: Vec<f32> = test.map(|num| num.trim().parse::<f32>().unwrap_or(0.0)).collect();
-
-    if camera.len() != 12 {
-        return;
-    }
-
-    let rot_mat: Matrix3<f32> = Matrix3::new(
-        camera[0], camera[1], camera[2],
-        camera[3], camera[4], camera[5],
-        camera[6], camera[7], camera[8]);
-
-    let quaternion: Quaternion<f32> = Quaternion::from(rot_mat);
+    let camera = match CameraTransform::parse(&metrics.camera.to_string()) {
+        Ok(camera) => camera,
+        Err(e) => {
+            let paragraph = Paragraph::new(format!("\nInvalid camera data: {e}"))
+                .style(Style::default().fg(Color::Red))
+                .block(Block::default().title("Camera").borders(Borders::ALL));
+            f.render_widget(paragraph, area);
+            return None;
+        }
+    };
 
-    let euler: Euler<Rad<f32>> = Euler::from(quaternion);
-    let rot_x = Deg::from(-euler.x);
-    let rot_y = Deg::from(-euler.y);
-    let rot_z = Deg::from(-euler.z);
+    let [rot_x, rot_y, rot_z] = camera.euler_degrees();
+    let [pos_x, pos_y, pos_z] = camera.position;
 
     let table = Table::new(vec![
         Row::new(vec![
             Cell::from("Rotation").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from(rot_x.0.to_string()),
-            Cell::from(rot_y.0.to_string()),
-            Cell::from(rot_z.0.to_string()),
+            Cell::from(rot_x.to_string()),
+            Cell::from(rot_y.to_string()),
+            Cell::from(rot_z.to_string()),
         ]).height(2),
         Row::new(vec![Cell::from("")]),
         Row::new(vec![
             Cell::from("Position").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from(camera.get(9).unwrap().to_string()),
-            Cell::from(camera.get(10).unwrap().to_string()),
-            Cell::from(camera.get(11).unwrap().to_string()),
+            Cell::from(pos_x.to_string()),
+            Cell::from(pos_y.to_string()),
+            Cell::from(pos_z.to_string()),
         ]).height(2),
     ])
         .style(Style::default().fg(Color::White))
@@ -131,9 +204,52 @@ fn draw_camera(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMet
         .column_spacing(1);
 
     f.render_widget(table, area);
+    Some(camera)
 }
 
-fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect)
+//top-down view of the camera, drawn over the location bounds and landmarks when known
+fn draw_minimap(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, app_data: &DataStore, area: Rect)
+{
+    let block = Block::default().title("Minimap").borders(Borders::ALL);
+    let camera = match CameraTransform::parse(&metrics.camera.to_string()) {
+        Ok(camera) => camera,
+        Err(_) => {
+            f.render_widget(block, area);
+            return;
+        }
+    };
+
+    let (x, y) = (camera.position[0] as f64, camera.position[1] as f64);
+    let map = app_data.minimaps.lookup(&metrics.scene.to_string());
+    let [min_x, min_y, max_x, max_y] = match map {
+        Some(map) => map.bounds,
+        None => [x - MINIMAP_RANGE, y - MINIMAP_RANGE, x + MINIMAP_RANGE, y + MINIMAP_RANGE],
+    };
+    let (heading_x, heading_y) = camera.heading();
+    let arrow_length = (max_x - min_x).max(max_y - min_y) / 12.0;
+
+    let canvas = Canvas::default()
+        .block(block)
+        .marker(if app_data.enhanced_graphics { Marker::Braille } else { Marker::Dot })
+        .x_bounds([min_x, max_x])
+        .y_bounds([min_y, max_y])
+        .paint(|ctx| {
+            if map.is_some() {
+                ctx.draw(&Rectangle { x: min_x, y: min_y, width: max_x - min_x, height: max_y - min_y, color: Color::DarkGray });
+            }
+            if let Some(map) = map {
+                for landmark in &map.landmarks {
+                    ctx.print(landmark.x, landmark.y, Span::styled(landmark.name.clone(), Style::default().fg(Color::Cyan)));
+                }
+            }
+            ctx.layer();
+            ctx.draw(&Line { x1: x, y1: y, x2: x + heading_x * arrow_length, y2: y + heading_y * arrow_length, color: Color::Yellow });
+            ctx.draw(&Points { coords: &[(x, y)], color: Color::LightRed });
+        });
+    f.render_widget(canvas, area);
+}
+
+fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, app_data: &DataStore, area: Rect)
 {
     let scene = metrics.scene.to_string();
     let scene_tag = vec![
@@ -141,18 +257,48 @@ fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetri
         Span::styled(&scene, Style::default().add_modifier(Modifier::BOLD)),
     ];
 
-    let uptimems = metrics.uptimems.to_string();
+    let scene_info = app_data.scenes.lookup(&scene)
+        .map(|info| info.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let location_tag = vec![
+        Span::raw("Location: "),
+        Span::styled(&scene_info, Style::default().add_modifier(Modifier::BOLD)),
+    ];
+
+    let timing = CrashTiming::new(metrics.uptimems, app_data.crash_time);
     let uptime_tag = vec![
         Span::raw("uptime: "),
-        Span::styled(&uptimems, Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" ms"),
+        Span::styled(&timing.uptime, Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!(" ({})", timing.bucket)),
     ];
 
 
     let mut text = Text::raw("\n");
     text.extend(Text::from(Spans::from(scene_tag)));
+    text.extend(Text::from(Spans::from(location_tag)));
+    for resource in scene_resource_paths(&scene) {
+        let id = runtime_id(&resource);
+        let known = match &app_data.hash_list {
+            Some(hash_list) if hash_list.lookup(id).is_some() => " (in hash list)",
+            Some(_) => " (not in hash list)",
+            None => "",
+        };
+        text.extend(Text::from(Spans::from(vec![
+            Span::raw("Runtime ID: "),
+            Span::styled(format_id(id), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(" {resource}{known}")),
+        ])));
+    }
     text.extend(Text::raw("\n"));
     text.extend(Text::from(Spans::from(uptime_tag)));
+    if let (Some(crash_time), Some(launch_time)) = (&timing.crash_time, &timing.launch_time) {
+        text.extend(Text::from(Spans::from(vec![
+            Span::raw("crashed around: "),
+            Span::styled(format_time(crash_time), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(", launched around: "),
+            Span::styled(format_time(launch_time), Style::default().add_modifier(Modifier::BOLD)),
+        ])));
+    }
     text.extend(Text::raw("\n"));
 
     let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("state"));
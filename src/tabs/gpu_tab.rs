@@ -3,20 +3,25 @@ use crossterm::event::KeyCode;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::text::Text;
-use tui::widgets::{Block, Borders, Paragraph};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, List, ListItem, Paragraph};
 use crate::app::DataStore;
+use crate::controls::stateful_tree::TreeNode;
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::gpu_report::GpuCrashReport;
 use crate::tabs::tab::Tab;
 
 pub struct GpuTab {
     pub title: String,
+    source: String,
 }
 
 impl GpuTab {
     pub fn new() -> GpuTab {
         GpuTab {
             title: "GPU".to_string(),
+            source: String::new(),
         }
     }
 }
@@ -24,14 +29,30 @@ impl GpuTab {
 impl Tab for GpuTab {
     fn on_load(&mut self, _app_data: &mut DataStore) {}
 
-    fn on_key(&mut self, _app_data: &mut DataStore, _key: KeyCode) {}
+    fn on_key(&mut self, app_data: &mut DataStore, key: KeyCode) {
+        match key {
+            KeyCode::Up => app_data.gpu_report.previous(),
+            KeyCode::Down => app_data.gpu_report.next(),
+            KeyCode::Enter => app_data.gpu_report.toggle(),
+            KeyCode::Right => app_data.gpu_report.expand(),
+            KeyCode::Left => app_data.gpu_report.collapse(),
+            _ => {}
+        }
+    }
 
     fn get_title(&self) -> &String {
         &self.title
     }
 
-    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, _app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
     {
+        let gpu_crash = metrics.gpu_crash_report.to_string();
+        if gpu_crash != self.source {
+            app_data.gpu_report.items = build_tree(&GpuCrashReport::parse(&gpu_crash));
+            app_data.gpu_report.select_highlighted();
+            self.source = gpu_crash;
+        }
+
         let chunks = Layout::default().direction(Direction::Horizontal)
             .constraints(
                 [
@@ -39,21 +60,108 @@ impl Tab for GpuTab {
                 ].as_ref(),
             )
             .split(area);
-        draw_info(f, metrics, chunks[0]);
+        draw_info(f, app_data, chunks[0]);
     }
 }
 
-fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect)
-{
-    let gpu_crash = metrics.gpu_crash_report.to_string();
+fn build_tree(report: &GpuCrashReport) -> Vec<TreeNode> {
+    let mut tree = vec![];
 
-    let mut text = Text::raw("\n");
-    if !gpu_crash.is_empty() {
-        text.extend(Text::raw(gpu_crash));
-    } else {
+    let mut removed = vec![];
+    if let Some(reason) = &report.device_removed.reason {
+        removed.push(TreeNode::leaf(format!("reason: {reason}")));
+        if let Some(description) = reason.description() {
+            removed.push(TreeNode::leaf(description.to_string()));
+        }
+    }
+    for (key, value) in &report.device_removed.lines {
+        removed.push(TreeNode::leaf(key_value(key, value)));
+    }
+    if !removed.is_empty() {
+        tree.push(TreeNode::branch("Device removed".to_string(), removed));
+    }
+
+    if !report.adapter.is_empty() {
+        let adapter = report.adapter.iter().map(|(key, value)| TreeNode::leaf(key_value(key, value))).collect();
+        tree.push(TreeNode::branch("Adapter and driver".to_string(), adapter));
+    }
+
+    for list in &report.breadcrumbs {
+        let last_completed = list.last_completed();
+        let ops = list.ops.iter().enumerate().map(|(i, op)| {
+            let done = list.completed.map(|completed| i < completed);
+            let marker = match done {
+                Some(true) => "[x]",
+                Some(false) => "[ ]",
+                None => "[?]",
+            };
+            let node = TreeNode::leaf(format!("{marker} {i}: {op}"));
+            if Some(i) == last_completed { node.highlighted() } else { node }
+        }).collect();
+        let title = match list.completed {
+            Some(completed) => format!("{} ({completed}/{} completed)", list.name, list.ops.len()),
+            None => list.name.clone(),
+        };
+        tree.push(TreeNode::branch(title, ops));
+    }
+
+    for fault in &report.page_faults {
+        let title = match fault.address {
+            Some(address) => format!("Page fault at 0x{address:x}"),
+            None => "Page fault".to_string(),
+        };
+        let allocations = fault.allocations.iter().map(|allocation| TreeNode::leaf(allocation.clone())).collect();
+        tree.push(TreeNode::branch(title, allocations));
+    }
+
+    if !report.unrecognized.is_empty() {
+        let lines = report.unrecognized.iter().map(|line| TreeNode::leaf(line.clone())).collect();
+        tree.push(TreeNode::branch("Other".to_string(), lines));
+    }
+    tree
+}
+
+fn key_value(key: &str, value: &str) -> String {
+    if key.is_empty() { value.to_string() } else { format!("{key}: {value}") }
+}
+
+fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, area: Rect)
+{
+    if app_data.gpu_report.items.is_empty() {
+        let mut text = Text::raw("\n");
         text.extend(Text::raw("No crash detected!"));
+        let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Gpu Crash Report"));
+        f.render_widget(paragraph, area);
+        return;
     }
 
-    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("Gpu Crash Report"));
-    f.render_widget(paragraph, area);
-}
\ No newline at end of file
+    let (expanded, collapsed) = if app_data.enhanced_graphics { ("▼ ", "▶ ") } else { ("- ", "+ ") };
+    let items: Vec<ListItem> = app_data.gpu_report
+        .rows()
+        .iter()
+        .map(|row| {
+            let marker = match (row.node.children.is_empty(), row.node.expanded) {
+                (true, _) => "  ",
+                (false, true) => expanded,
+                (false, false) => collapsed,
+            };
+            let style = if row.node.highlight {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Spans::from(vec![
+                Span::raw("  ".repeat(row.depth)),
+                Span::raw(marker),
+                Span::styled(row.node.label.clone(), style),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Gpu Crash Report"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol("> ");
+
+    f.render_stateful_widget(list, area, &mut app_data.gpu_report.state);
+}
@@ -6,4 +6,6 @@ pub mod gpu_tab;
 pub mod vr_tab;
 pub mod module_tab;
 pub mod callstack_tab;
-pub mod exception_tab;
\ No newline at end of file
+pub mod exception_tab;
+pub mod corpus_tab;
+pub mod statistics_tab;
\ No newline at end of file
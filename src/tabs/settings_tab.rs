@@ -1,89 +1,343 @@
 use std::io::Stdout;
 use crossterm::event::KeyCode;
+use native_dialog::FileDialog;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::text::Text;
-use tui::widgets::{Block, Borders, Paragraph};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
 use crate::app::DataStore;
+use crate::crash_export::load_metrics;
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::graphics_presets::{closest_preset, GraphicsPreset};
+use crate::graphics_settings::{GraphicsSettings, Setting};
+use crate::settings_profile::{read_profile, ProfileFormat};
 use crate::tabs::tab::Tab;
 
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    Original,
+    Key,
+    Type,
+    Changed,
+}
+
+impl SortMode {
+    fn next(self) -> SortMode {
+        match self {
+            SortMode::Original => SortMode::Key,
+            SortMode::Key => SortMode::Type,
+            SortMode::Type => SortMode::Changed,
+            SortMode::Changed => SortMode::Original,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            SortMode::Original => "original",
+            SortMode::Key => "name",
+            SortMode::Type => "type",
+            SortMode::Changed => "changed first",
+        }
+    }
+}
+
+enum PresetChoice {
+    None,
+    Closest,
+    Bundled(usize),
+    PreviousCrash,
+    File(GraphicsPreset),
+}
+
 pub struct SettingsTab {
     pub title: String,
+    sort: SortMode,
+    query: String,
+    searching: bool,
+    presets: Vec<GraphicsPreset>,
+    choice: PresetChoice,
+    state: TableState,
+    message: Option<String>,
+    //settings of the crash drawn last, used by the export
+    settings: GraphicsSettings,
 }
 
 impl SettingsTab {
     pub fn new() -> SettingsTab {
         SettingsTab {
             title: "Settings".to_string(),
+            sort: SortMode::Original,
+            query: String::new(),
+            searching: false,
+            presets: GraphicsPreset::bundled(),
+            choice: PresetChoice::Closest,
+            state: TableState::default(),
+            message: None,
+            settings: GraphicsSettings::default(),
+        }
+    }
+
+    fn choose_preset_file(&mut self) {
+        let path = FileDialog::new()
+            .set_location("~/AppData")
+            .add_filter("crash_metrics", &["dat", "json"])
+            .add_filter("settings profile", &["cfg"])
+            .show_open_single_file();
+
+        if let Ok(Some(path)) = path {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+            let settings = if ProfileFormat::from_path(&path).is_some() {
+                read_profile(&path)
+            } else {
+                load_metrics(&path.to_string_lossy()).map(|metrics| GraphicsSettings::parse(&metrics.settings_info.to_string()))
+            };
+            match settings {
+                Ok(settings) => self.choice = PresetChoice::File(GraphicsPreset::new(name, settings)),
+                Err(e) => self.message = Some(format!("Failed to load {name}: {e}")),
+            }
+        }
+    }
+
+    fn export_profile(&mut self) {
+        let path = FileDialog::new()
+            .set_location("~/Documents")
+            .add_filter("settings profile", &["cfg"])
+            .add_filter("registry file", &["reg"])
+            .show_save_single_file();
+
+        let path = match path {
+            Ok(Some(path)) => path,
+            _ => return,
+        };
+
+        let format = ProfileFormat::from_path(&path).unwrap_or(ProfileFormat::KeyValue);
+        self.message = Some(match std::fs::write(&path, format.write(&self.settings)) {
+            Ok(_) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Failed to export: {e}"),
+        });
+    }
+
+    //closest -> bundled presets -> previous crash (while live watching) -> closest
+    fn next_preset(&mut self, app_data: &DataStore) {
+        self.choice = match self.choice {
+            PresetChoice::Closest if !self.presets.is_empty() => PresetChoice::Bundled(0),
+            PresetChoice::Bundled(i) if i + 1 < self.presets.len() => PresetChoice::Bundled(i + 1),
+            PresetChoice::Bundled(_) if app_data.previous_settings.is_some() => PresetChoice::PreviousCrash,
+            _ => PresetChoice::Closest,
+        };
+    }
+
+    fn resolve_preset(&self, settings: &GraphicsSettings, app_data: &DataStore) -> Option<(GraphicsPreset, String)> {
+        match &self.choice {
+            PresetChoice::None => None,
+            PresetChoice::Closest => closest_preset(&self.presets, settings)
+                .map(|(preset, deviations)| (preset.clone(), format!("closest: {} ({deviations} deviations)", preset.name))),
+            PresetChoice::Bundled(i) => self.presets.get(*i).map(|preset| (preset.clone(), preset.name.clone())),
+            PresetChoice::PreviousCrash => app_data.previous_settings.as_ref()
+                .map(|previous| (GraphicsPreset::new("previous crash".to_string(), previous.clone()), "previous crash".to_string())),
+            PresetChoice::File(preset) => Some((preset.clone(), preset.name.clone())),
         }
     }
+
+    fn visible<'a>(&self, settings: &'a GraphicsSettings, preset: Option<&GraphicsPreset>) -> Vec<&'a Setting> {
+        let query = self.query.to_lowercase();
+        let mut visible: Vec<&Setting> = settings.entries.iter()
+            .filter(|setting| query.is_empty()
+                || setting.key.to_lowercase().contains(&query)
+                || setting.value.to_string().to_lowercase().contains(&query))
+            .collect();
+        match self.sort {
+            SortMode::Original => {}
+            SortMode::Key => visible.sort_by_key(|setting| setting.key.to_lowercase()),
+            SortMode::Type => visible.sort_by_key(|setting| setting.value.type_name()),
+            SortMode::Changed => visible.sort_by_key(|setting| preset.and_then(|preset| preset.deviation(setting)).is_none()),
+        }
+        visible
+    }
+
+    fn select(&mut self, offset: isize) {
+        let i = self.state.selected().unwrap_or(0) as isize + offset;
+        self.state.select(Some(i.max(0) as usize));
+    }
 }
 
 
 impl Tab for SettingsTab {
     fn on_load(&mut self, _app_data: &mut DataStore) {}
 
-    fn on_key(&mut self, _app_data: &mut DataStore, _key: KeyCode) {}
+    fn on_key(&mut self, app_data: &mut DataStore, key: KeyCode) {
+        if self.searching {
+            match key {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => { self.query.pop(); }
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                _ => {}
+            }
+            self.state.select(Some(0));
+            return;
+        }
+
+        match key {
+            KeyCode::Up => self.select(-1),
+            KeyCode::Down => self.select(1),
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('o') => self.sort = self.sort.next(),
+            KeyCode::Char('p') => self.next_preset(app_data),
+            KeyCode::Char('f') => self.choose_preset_file(),
+            KeyCode::Char('x') => self.choice = PresetChoice::None,
+            KeyCode::Char('e') => self.export_profile(),
+            _ => {}
+        }
+    }
 
     fn get_title(&self) -> &String {
         &self.title
     }
 
-    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, _app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
+    fn captures_input(&self) -> bool {
+        self.searching
+    }
+
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect)
     {
-        let chunks = Layout::default().direction(Direction::Horizontal)
+        let settings = GraphicsSettings::parse(&metrics.settings_info.to_string());
+        let preset = self.resolve_preset(&settings, app_data);
+
+        let chunks = Layout::default().direction(Direction::Vertical)
             .constraints(
                 [
-                    Constraint::Percentage(100),
+                    Constraint::Length(4),
+                    Constraint::Min(0),
                 ].as_ref(),
             )
             .split(area);
-        draw_settings(f, metrics, chunks[0]);
+        let chunks_bottom = Layout::default().direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(65),
+                    Constraint::Percentage(35),
+                ].as_ref(),
+            )
+            .split(chunks[1]);
+
+        let preset_label = preset.as_ref().map(|(_, label)| label.as_str()).unwrap_or("none");
+        draw_status(f, self, preset_label, chunks[0]);
+        let preset = preset.as_ref().map(|(preset, _)| preset);
+        draw_settings(f, self, &settings, preset, chunks_bottom[0]);
+        draw_deviations(f, &settings, preset, chunks_bottom[1]);
+        self.settings = settings;
     }
 }
 
-fn draw_settings(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetrics, area: Rect)
+fn draw_status(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &SettingsTab, preset: &str, area: Rect)
 {
-    let chunks = Layout::default().direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage(5),
-                Constraint::Percentage(45),
-                Constraint::Percentage(45),
-                Constraint::Percentage(5),
-            ].as_ref(),
-        )
-        .split(area);
-
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let key = Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD);
+    let search_style = if tab.searching { bold.fg(Color::Yellow) } else { bold };
+    let status = vec![
+        Span::styled("/", key),
+        Span::raw(" search: "),
+        Span::styled(format!("{}{}", tab.query, if tab.searching { "_" } else { "" }), search_style),
+        Span::raw("   s"),
+        Span::styled("o", key),
+        Span::raw("rt: "),
+        Span::styled(tab.sort.name(), bold),
+        Span::raw("   "),
+        Span::styled("p", key),
+        Span::raw("reset ("),
+        Span::styled("f", key),
+        Span::raw("ile, "),
+        Span::styled("x", key),
+        Span::raw(" clears): "),
+        Span::styled(preset, bold),
+        Span::raw("   "),
+        Span::styled("e", key),
+        Span::raw("xport profile"),
+    ];
+    let mut text = Text::from(Spans::from(status));
+    if let Some(message) = &tab.message {
+        text.extend(Text::raw(format!(" {message}")));
+    }
 
-    let settings = metrics.settings_info.to_string();
+    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL));
+    f.render_widget(paragraph, area);
+}
 
-    let binding = settings.replace("Graphics Settings:", "");
-    let lines = binding.split('\n').collect::<Vec<&str>>();
+fn draw_settings(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &mut SettingsTab, settings: &GraphicsSettings, preset: Option<&GraphicsPreset>, area: Rect)
+{
+    let block = Block::default().borders(Borders::ALL).title("Graphics Settings");
 
-    let (left_lines, right_lines) = lines.split_at(lines.len() / 2);
+    if settings.is_empty() {
+        let mut text = Text::raw("\n");
+        text.extend(Text::raw(" No graphics settings found!"));
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
 
-    let mut left = Text::raw("\n\n");
-    for line in left_lines {
-        left.extend(Text::raw(*line));
+    let visible = tab.visible(settings, preset);
+    if let Some(selected) = tab.state.selected() {
+        tab.state.select(Some(selected.min(visible.len().saturating_sub(1))));
     }
 
+    let rows: Vec<Row> = visible.iter()
+        .map(|setting| {
+            let mut cells = vec![
+                Cell::from(setting.key.clone()).style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(setting.value.to_string()),
+                Cell::from(setting.value.type_name()).style(Style::default().fg(Color::DarkGray)),
+            ];
+            if let Some(preset) = preset {
+                cells.push(Cell::from(preset.settings.get(&setting.key).map(|value| value.to_string()).unwrap_or_else(|| "-".to_string())));
+            }
+            let differs = preset.and_then(|preset| preset.deviation(setting)).is_some();
+            let style = if differs { Style::default().fg(Color::Yellow) } else { Style::default() };
+            Row::new(cells).style(style)
+        })
+        .collect();
 
-    let mut right = Text::raw("\n\n");
-    for line in right_lines {
-        right.extend(Text::raw(*line));
+    let mut header = vec!["Setting", "Value", "Type"];
+    if preset.is_some() {
+        header.push("Preset");
     }
 
+    let table = Table::new(rows)
+        .header(
+            Row::new(header)
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1)
+        )
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&[Constraint::Percentage(40), Constraint::Percentage(25), Constraint::Percentage(10), Constraint::Percentage(25)])
+        .column_spacing(1);
 
-    let paragraph = Paragraph::new(left);
-    f.render_widget(paragraph, chunks[1]);
-
-    let paragraph = Paragraph::new(right);
-    f.render_widget(paragraph, chunks[2]);
+    f.render_stateful_widget(table, area, &mut tab.state);
+}
 
+fn draw_deviations(f: &mut Frame<CrosstermBackend<Stdout>>, settings: &GraphicsSettings, preset: Option<&GraphicsPreset>, area: Rect)
+{
+    let mut text = Text::raw("\n");
+    match preset {
+        Some(preset) => {
+            let deviations = preset.deviations(settings);
+            if deviations.is_empty() {
+                text.extend(Text::raw(format!(" Matches {}", preset.name)));
+            }
+            for deviation in deviations {
+                text.extend(Text::from(Spans::from(Span::styled(format!(" {}", deviation.describe(&preset.name)), Style::default().fg(Color::Yellow)))));
+            }
+        }
+        None => text.extend(Text::raw(" No preset selected")),
+    }
 
-    let block = Block::default().borders(Borders::ALL).title("Graphics Settings");
-    f.render_widget(block, area);
-}
\ No newline at end of file
+    let paragraph = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Deviations"));
+    f.render_widget(paragraph, area);
+}
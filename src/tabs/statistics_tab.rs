@@ -0,0 +1,238 @@
+use std::io::Stdout;
+use crossterm::event::KeyCode;
+use tui::backend::CrosstermBackend;
+use tui::Frame;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::symbols::Marker;
+use tui::text::{Span, Spans, Text};
+use tui::widgets::{Axis, BarChart, Block, Borders, Chart, Dataset, GraphType, List, ListItem, ListState, Paragraph};
+use crate::app::DataStore;
+use crate::batch::count_by;
+use crate::corpus::{Dimension, Facet, DIMENSIONS};
+use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::tabs::tab::Tab;
+use crate::timing::format_duration;
+
+const MIN_BAR_WIDTH: u16 = 3;
+const MAX_BAR_WIDTH: u16 = 12;
+const THRESHOLD_STEP: f64 = 0.05;
+
+pub struct StatisticsTab {
+    pub title: String,
+    dimension: usize,
+    bar: usize,
+    //counts of the dimension drawn last, used by the key handlers
+    counts: Vec<(String, usize)>,
+    //corpus generation and dimension the counts were made for
+    counted: Option<(u64, usize)>,
+}
+
+impl StatisticsTab {
+    pub fn new() -> StatisticsTab {
+        StatisticsTab {
+            title: "Statistics".to_string(),
+            dimension: 0,
+            bar: 0,
+            counts: vec![],
+            counted: None,
+        }
+    }
+
+    fn dimension(&self) -> Dimension {
+        DIMENSIONS[self.dimension]
+    }
+
+    fn select_dimension(&mut self, offset: isize) {
+        let count = DIMENSIONS.len() as isize;
+        self.dimension = (self.dimension as isize + offset).rem_euclid(count) as usize;
+        self.bar = 0;
+    }
+
+    fn select_bar(&mut self, offset: isize) {
+        if self.counts.is_empty() {
+            return;
+        }
+        let count = self.counts.len() as isize;
+        self.bar = (self.bar as isize + offset).rem_euclid(count) as usize;
+    }
+}
+
+impl Tab for StatisticsTab {
+    fn on_load(&mut self, _app_data: &mut DataStore) {}
+
+    fn on_key(&mut self, app_data: &mut DataStore, key: KeyCode) {
+        match key {
+            KeyCode::Up => self.select_dimension(-1),
+            KeyCode::Down => self.select_dimension(1),
+            KeyCode::Left => self.select_bar(-1),
+            KeyCode::Right => self.select_bar(1),
+            KeyCode::Char('+') => app_data.corpus.set_cluster_threshold(app_data.corpus.cluster_threshold + THRESHOLD_STEP),
+            KeyCode::Char('-') => app_data.corpus.set_cluster_threshold(app_data.corpus.cluster_threshold - THRESHOLD_STEP),
+            KeyCode::Enter => {
+                if let Some((value, _)) = self.counts.get(self.bar) {
+                    app_data.corpus_facet = Some(Facet { dimension: self.dimension(), value: value.clone() });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn get_title(&self) -> &String {
+        &self.title
+    }
+
+    fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, _metrics: &mut G2CrashMetrics, area: Rect)
+    {
+        let dimension = self.dimension();
+        if self.counted != Some((app_data.corpus.generation, self.dimension)) {
+            self.counts = count_by(&app_data.corpus.entries, |entry| dimension.value(entry));
+            self.counted = Some((app_data.corpus.generation, self.dimension));
+        }
+        self.bar = self.bar.min(self.counts.len().saturating_sub(1));
+
+        let chunks = Layout::default().direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(4),
+                    Constraint::Min(0),
+                    Constraint::Length(12),
+                ].as_ref(),
+            )
+            .split(area);
+        let chunks_middle = Layout::default().direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(55),
+                    Constraint::Percentage(45),
+                ].as_ref(),
+            )
+            .split(chunks[1]);
+
+        draw_status(f, self, app_data, chunks[0]);
+        if app_data.corpus.entries.is_empty() {
+            let mut text = Text::raw("\n");
+            text.extend(Text::raw(" No crashes to count, load a corpus on the corpus tab first"));
+            f.render_widget(Paragraph::new(text).block(Block::default().borders(Borders::ALL)), chunks[1]);
+            return;
+        }
+        draw_bars(f, self, chunks_middle[0]);
+        draw_legend(f, self, app_data.corpus.entries.len(), chunks_middle[1]);
+        draw_uptime(f, app_data, chunks[2]);
+    }
+}
+
+fn draw_status(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &StatisticsTab, app_data: &DataStore, area: Rect)
+{
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let key = Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD);
+    let mut dimensions = vec![Span::raw(" crashes per ")];
+    for (i, dimension) in DIMENSIONS.iter().enumerate() {
+        if i > 0 {
+            dimensions.push(Span::raw(" | "));
+        }
+        let style = if i == tab.dimension { bold.fg(Color::Yellow) } else { Style::default() };
+        dimensions.push(Span::styled(dimension.name(), style));
+    }
+
+    let hints = vec![
+        Span::styled(" Up/Down", key),
+        Span::raw(" grouping   "),
+        Span::styled("Left/Right", key),
+        Span::raw(" bar   "),
+        Span::styled("Enter", key),
+        Span::raw(" lists the crashes of the bar in the corpus tab   "),
+        Span::styled("+/-", key),
+        Span::raw(" cluster similarity: "),
+        Span::styled(format!("{:.2}", app_data.corpus.cluster_threshold), bold),
+        Span::raw(format!("   {} crashes, {} clusters", app_data.corpus.entries.len(), app_data.corpus.clusters.len())),
+    ];
+    let mut text = Text::from(Spans::from(dimensions));
+    text.extend(Text::from(Spans::from(hints)));
+    f.render_widget(Paragraph::new(text).block(Block::default().borders(Borders::ALL)), area);
+}
+
+fn draw_bars(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &StatisticsTab, area: Rect)
+{
+    let block = Block::default().borders(Borders::ALL).title(format!("Crashes per {}", tab.dimension().name()));
+    let width = block.inner(area).width;
+
+    //bars are numbered like the legend, only the ones around the selection fit when there are many
+    let bar_width = (width / tab.counts.len().max(1) as u16).saturating_sub(1).clamp(MIN_BAR_WIDTH, MAX_BAR_WIDTH);
+    let fitting = ((width / (bar_width + 1)) as usize).max(1);
+    let first = tab.bar.saturating_sub(fitting - 1);
+    let labels: Vec<String> = (first..tab.counts.len().min(first + fitting))
+        .map(|i| if i == tab.bar { format!(">{}", i + 1) } else { (i + 1).to_string() })
+        .collect();
+    let data: Vec<(&str, u64)> = labels.iter()
+        .zip(&tab.counts[first..])
+        .map(|(label, (_, count))| (label.as_str(), *count as u64))
+        .collect();
+
+    let chart = BarChart::default()
+        .block(block)
+        .data(&data)
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::LightRed))
+        .value_style(Style::default().fg(Color::Black).bg(Color::LightRed).add_modifier(Modifier::BOLD))
+        .label_style(Style::default().fg(Color::Yellow));
+    f.render_widget(chart, area);
+}
+
+fn draw_legend(f: &mut Frame<CrosstermBackend<Stdout>>, tab: &StatisticsTab, total: usize, area: Rect)
+{
+    let items: Vec<ListItem> = tab.counts.iter().enumerate()
+        .map(|(i, (value, count))| {
+            let share = *count as f64 / total as f64 * 100.0;
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{:>3}. ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{count:>4} ({share:>3.0}%) "), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(value.clone()),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Legend"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(Some(tab.bar));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+//share of crashes that happened within a given uptime
+fn draw_uptime(f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &DataStore, area: Rect)
+{
+    let mut uptimes: Vec<f64> = app_data.corpus.entries.iter().map(|entry| entry.uptime_ms as f64).collect();
+    uptimes.sort_by(|a, b| a.total_cmp(b));
+    let total = uptimes.len() as f64;
+    let mut points = vec![(0.0, 0.0)];
+    for (i, uptime) in uptimes.iter().enumerate() {
+        points.push((*uptime, i as f64 / total * 100.0));
+        points.push((*uptime, (i + 1) as f64 / total * 100.0));
+    }
+    let max = uptimes.last().copied().unwrap_or(0.0).max(1.0);
+
+    let datasets = vec![
+        Dataset::default()
+            .name("crashes within uptime")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::LightRed))
+            .data(&points),
+    ];
+    let chart = Chart::new(datasets)
+        .block(Block::default().borders(Borders::ALL).title("Uptime distribution"))
+        .x_axis(Axis::default()
+            .title("uptime")
+            .bounds([0.0, max])
+            .labels(vec![
+                Span::raw("0"),
+                Span::raw(format_duration((max / 2.0) as u64)),
+                Span::raw(format_duration(max as u64)),
+            ]))
+        .y_axis(Axis::default()
+            .bounds([0.0, 100.0])
+            .labels(vec![Span::raw("0%"), Span::raw("50%"), Span::raw("100%")]));
+    f.render_widget(chart, area);
+}
@@ -3,11 +3,12 @@ use crossterm::event::KeyCode;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::style::{Modifier, Style};
+use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans, Text};
-use tui::widgets::{Block, Borders, Paragraph};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
 use crate::app::DataStore;
 use crate::g2_crash_metrics::G2CrashMetrics;
+use crate::system_info::SystemInfo;
 use crate::tabs::tab::Tab;
 
 pub struct SystemTab {
@@ -56,13 +57,27 @@ fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetri
         )
         .split(area);
 
-    let info = metrics.system_info.to_string();
+    let info = SystemInfo::parse(&metrics.system_info.to_string());
 
-    let mut text = Text::raw(info.replace("System Info:", ""));
-    text.extend(Text::raw("\n"));
+    let mut rows: Vec<Row> = info.rows().into_iter()
+        .map(|(key, value)| Row::new(vec![
+            Cell::from(key).style(Style::default().add_modifier(Modifier::BOLD)),
+            Cell::from(value),
+        ]))
+        .collect();
+    for (key, value) in info.other {
+        rows.push(Row::new(vec![
+            Cell::from(key).style(Style::default().fg(Color::DarkGray)),
+            Cell::from(value),
+        ]));
+    }
 
-    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("System Info"));
-    f.render_widget(paragraph, chunks[0]);
+    let table = Table::new(rows)
+        .style(Style::default().fg(Color::White))
+        .block(Block::default().borders(Borders::ALL).title("System Info"))
+        .widths(&[Constraint::Length(20), Constraint::Percentage(80)])
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
 
 
     //OS block
@@ -10,5 +10,9 @@ pub trait Tab {
     fn on_load(&mut self, app_data: &mut DataStore);
     fn on_key(&mut self, app_data: &mut DataStore, key: KeyCode);
     fn get_title(&self) -> &String;
+    //while true, every key press goes to the tab instead of the global shortcuts
+    fn captures_input(&self) -> bool {
+        false
+    }
     fn draw(&mut self, f: &mut Frame<CrosstermBackend<Stdout>>, app_data: &mut DataStore, metrics: &mut G2CrashMetrics, area: Rect);
 }
\ No newline at end of file
@@ -3,11 +3,13 @@ use crossterm::event::KeyCode;
 use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
 use tui::text::Text;
-use tui::widgets::{Block, Borders, Paragraph};
+use tui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
 use crate::app::DataStore;
 use crate::g2_crash_metrics::G2CrashMetrics;
 use crate::tabs::tab::Tab;
+use crate::vr_info::VrInfo;
 
 pub struct VrTab {
     pub title: String,
@@ -41,24 +43,49 @@ fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetri
     let chunks = Layout::default().direction(Direction::Vertical)
         .constraints(
             [
+                Constraint::Percentage(40),
                 Constraint::Percentage(20),
-                Constraint::Percentage(80),
+                Constraint::Percentage(40),
             ].as_ref(),
         )
         .split(area);
 
 
     let vr_data = metrics.vr_data.to_string();
+    let vr_desc = metrics.vr_hdm_description.to_string();
+    let info = VrInfo::parse(&vr_data, &vr_desc);
+    let in_use = !info.is_empty();
+
+    let mut rows: Vec<Row> = info.rows().into_iter()
+        .map(|(key, value)| Row::new(vec![
+            Cell::from(key).style(Style::default().add_modifier(Modifier::BOLD)),
+            Cell::from(value),
+        ]))
+        .collect();
+    for (key, value) in info.other {
+        rows.push(Row::new(vec![
+            Cell::from(key).style(Style::default().fg(Color::DarkGray)),
+            Cell::from(value),
+        ]));
+    }
+    if !in_use {
+        rows.push(Row::new(vec![Cell::from("No VR headset in use")]));
+    }
+
+    let table = Table::new(rows)
+        .style(Style::default().fg(Color::White))
+        .block(Block::default().borders(Borders::ALL).title("VR headset"))
+        .widths(&[Constraint::Length(20), Constraint::Percentage(80)])
+        .column_spacing(1);
+    f.render_widget(table, chunks[0]);
 
     let mut text = Text::raw("\n");
     text.extend(Text::raw(vr_data));
 
 
     let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("VR data"));
-    f.render_widget(paragraph, chunks[0]);
-
+    f.render_widget(paragraph, chunks[1]);
 
-    let vr_desc = metrics.vr_hdm_description.to_string();
 
     let mut text = Text::raw("\n");
     if !vr_desc.is_empty() {
@@ -68,5 +95,5 @@ fn draw_info(f: &mut Frame<CrosstermBackend<Stdout>>, metrics: &mut G2CrashMetri
     }
 
     let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("VR header description"));
-    f.render_widget(paragraph, chunks[1]);
+    f.render_widget(paragraph, chunks[2]);
 }
\ No newline at end of file
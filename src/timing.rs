@@ -0,0 +1,151 @@
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use schemars::JsonSchema;
+use serde::Serialize;
+use crate::crash_export::exported_crash_time;
+use crate::exporters::ExportFormat;
+
+//crashes within the first minute happen before the main menu is up
+const STARTUP_MS: u64 = 60 * 1000;
+//the first few minutes are spent loading into the menu and the first mission
+const LOADING_MS: u64 = 5 * 60 * 1000;
+const LONG_SESSION_MS: u64 = 2 * 60 * 60 * 1000;
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, JsonSchema)]
+pub enum UptimeBucket {
+    Startup,
+    Loading,
+    Session,
+    LongSession,
+}
+
+impl UptimeBucket {
+    pub fn from_uptime(uptime_ms: u64) -> UptimeBucket {
+        if uptime_ms < STARTUP_MS {
+            UptimeBucket::Startup
+        } else if uptime_ms < LOADING_MS {
+            UptimeBucket::Loading
+        } else if uptime_ms < LONG_SESSION_MS {
+            UptimeBucket::Session
+        } else {
+            UptimeBucket::LongSession
+        }
+    }
+}
+
+impl fmt::Display for UptimeBucket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UptimeBucket::Startup => write!(f, "startup crash"),
+            UptimeBucket::Loading => write!(f, "loading crash"),
+            UptimeBucket::Session => write!(f, "in-session crash"),
+            UptimeBucket::LongSession => write!(f, "long-session crash"),
+        }
+    }
+}
+
+//e.g. "2h 13m 05s", sub-second uptimes keep their milliseconds
+pub fn format_duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m {seconds:02}s")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else if seconds > 0 {
+        format!("{seconds}s")
+    } else {
+        format!("{ms} ms")
+    }
+}
+
+pub fn format_time(time: &DateTime<Local>) -> String {
+    time.format(TIME_FORMAT).to_string()
+}
+
+//reads back a time written by format_time
+pub fn parse_time(time: &str) -> Option<SystemTime> {
+    let time = NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+    Local.from_local_datetime(&time).earliest().map(SystemTime::from)
+}
+
+//the game writes crash_metrics.dat when it crashes. exports were written later, so their
+//modification time says nothing and the crash time saved in them is used instead
+pub fn crash_file_time(path: &Path) -> Option<SystemTime> {
+    if let Some(format) = ExportFormat::from_path(path) {
+        return std::fs::read(path).ok().and_then(|bytes| exported_crash_time(&bytes, format));
+    }
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CrashTiming {
+    pub uptime_ms: u64,
+    pub uptime: String,
+    pub bucket: UptimeBucket,
+    //the bucket as the rest of the tool shows it, for templates
+    pub bucket_name: String,
+    //approximate, reconstructed from the modification time of the crash file
+    pub crash_time: Option<DateTime<Local>>,
+    pub launch_time: Option<DateTime<Local>>,
+}
+
+impl CrashTiming {
+    pub fn new(uptime_ms: u64, crash_time: Option<SystemTime>) -> CrashTiming {
+        let launch_time = crash_time.and_then(|time| time.checked_sub(Duration::from_millis(uptime_ms)));
+        CrashTiming {
+            uptime_ms,
+            uptime: format_duration(uptime_ms),
+            bucket: UptimeBucket::from_uptime(uptime_ms),
+            bucket_name: UptimeBucket::from_uptime(uptime_ms).to_string(),
+            crash_time: crash_time.map(DateTime::from),
+            launch_time: launch_time.map(DateTime::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crash_export::CrashExport;
+    use crate::g2_crash_metrics::G2CrashMetrics;
+    use crate::scenes::SceneTable;
+
+    #[test]
+    fn exports_keep_the_saved_crash_time() {
+        let crashed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let metrics = G2CrashMetrics { uptimems: 90_000, ..Default::default() };
+        let export = CrashExport::new(&metrics, &SceneTable::bundled(), Some(crashed));
+        for format in [ExportFormat::Json, ExportFormat::Yaml, ExportFormat::Toml, ExportFormat::Cbor, ExportFormat::MessagePack] {
+            let path = std::env::temp_dir().join(format!("timing-test-{}.{}", std::process::id(), format.extension()));
+            std::fs::write(&path, format.write(std::slice::from_ref(&export)).unwrap()).unwrap();
+            let time = crash_file_time(&path);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(time, Some(crashed), "{}", format.name());
+        }
+    }
+
+    #[test]
+    fn exports_without_a_saved_time_have_none() {
+        let path = std::env::temp_dir().join(format!("timing-test-{}-unversioned.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_vec(&G2CrashMetrics::default()).unwrap()).unwrap();
+        let time = crash_file_time(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(time, None);
+    }
+
+    #[test]
+    fn uptime_buckets() {
+        assert_eq!(UptimeBucket::from_uptime(59_999), UptimeBucket::Startup);
+        assert_eq!(UptimeBucket::from_uptime(60_000), UptimeBucket::Loading);
+        assert_eq!(UptimeBucket::from_uptime(5 * 60 * 1000), UptimeBucket::Session);
+        assert_eq!(UptimeBucket::from_uptime(2 * 60 * 60 * 1000), UptimeBucket::LongSession);
+        assert_eq!(format_duration(7_985_000), "2h 13m 05s");
+        assert_eq!(format_duration(65_000), "1m 05s");
+        assert_eq!(format_duration(250), "250 ms");
+    }
+}
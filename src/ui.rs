@@ -1,11 +1,12 @@
 use std::io::Stdout;
 use tui::backend::{CrosstermBackend};
 use tui::Frame;
-use tui::layout::{Constraint, Direction, Layout};
+use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
 use tui::text::{Span, Spans, Text};
 use tui::widgets::{Block, Borders, Paragraph, Tabs};
 use crate::app::App;
+use crate::online::OnlineInfo;
 
 
 pub fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
@@ -19,8 +20,15 @@ pub fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
         .enumerate()
         .map(|(i,t)| Spans::from(Span::styled(format!("{}: {}", i+1, *t), Style::default().fg(Color::LightRed))))
         .collect();
+    //private servers change a lot of online behaviour, so flag them on every tab
+    let online = OnlineInfo::parse(&app.metrics.net_role.to_string(), &app.metrics.online_server_version.to_string());
+    let mut title = vec![Span::raw(app.title)];
+    if online.server_kind.is_private() {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(format!(" private server: {} ", online.server_kind), Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title(app.title))
+        .block(Block::default().borders(Borders::ALL).title(Spans::from(title)))
         .highlight_style(Style::default().fg(Color::Yellow))
         .select(app.tabs.index);
     f.render_widget(tabs, chunks[0]);
@@ -41,16 +49,22 @@ pub fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
 
         let live_update = app.data.should_live_update.to_string();
         let controls = vec![
-            Span::styled(" 1-8 ", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
+            Span::styled(" 1-0 ", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
             Span::styled("switch tab", Style::default()),
             Span::raw(" ――― "),
 
             Span::styled("s", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
-            Span::styled("ave or ", Style::default()),
+            Span::styled("ave (", Style::default()),
+            Span::styled("S", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(": {}) or ", app.data.export_format.name()), Style::default()),
             Span::styled("i", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
             Span::styled("mport file", Style::default()),
             Span::raw(" ――― "),
 
+            Span::styled("r", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
+            Span::styled("eport", Style::default()),
+            Span::raw(" ――― "),
+
             Span::styled("Toggle ", Style::default()),
             Span::styled("l", Style::default().add_modifier(Modifier::UNDERLINED).add_modifier(Modifier::BOLD)),
             Span::styled("ive refresh (", Style::default()),
@@ -63,6 +77,15 @@ pub fn draw(f: &mut Frame<CrosstermBackend<Stdout>>, app: &mut App) {
         ];
 
         f.render_widget( Paragraph::new(Text::from(Spans::from(controls))), align2[1]);
+
+        //status messages sit on the bottom border of the header
+        if let Some(message) = &app.data.status_msg {
+            let area = chunks[0];
+            let status_area = Rect::new(area.x + 2, area.y + area.height.saturating_sub(1), area.width.saturating_sub(4), 1);
+            let paragraph = Paragraph::new(Span::styled(format!(" {message} "), Style::default().fg(Color::Yellow)))
+                .alignment(Alignment::Right);
+            f.render_widget(paragraph, status_area);
+        }
     }
 
 
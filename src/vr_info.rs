@@ -0,0 +1,124 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::system_info::{first_number, parse_resolution};
+
+const RUNTIMES: [(&str, &str); 7] = [
+    ("openxr", "OpenXR"),
+    ("steamvr", "SteamVR"),
+    ("openvr", "OpenVR"),
+    ("oculus", "Oculus"),
+    ("windows mixed reality", "Windows Mixed Reality"),
+    ("wmr", "Windows Mixed Reality"),
+    ("psvr", "PlayStation VR"),
+];
+
+//model substrings that give away the vendor when the description doesn't name it
+const VENDORS: [(&str, &str); 10] = [
+    ("index", "Valve"),
+    ("quest", "Meta"),
+    ("rift", "Meta"),
+    ("vive", "HTC"),
+    ("reverb", "HP"),
+    ("pimax", "Pimax"),
+    ("varjo", "Varjo"),
+    ("pico", "Pico"),
+    ("odyssey", "Samsung"),
+    ("psvr", "Sony"),
+];
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VrInfo {
+    pub runtime: Option<String>,
+    pub hmd_model: Option<String>,
+    pub hmd_vendor: Option<String>,
+    pub refresh_rate_hz: Option<f64>,
+    pub render_resolution: Option<(u32, u32)>,
+    pub tracking: Option<String>,
+    //every entry that could not be mapped onto one of the fields above
+    pub other: Vec<(String, String)>,
+}
+
+impl VrInfo {
+    //vr_data is a short ';' separated list, the hmd description has one "key: value" per line
+    pub fn parse(vr_data: &str, hmd_description: &str) -> VrInfo {
+        let mut info = VrInfo::default();
+        let entries = vr_data.split([';', '\n'])
+            .chain(hmd_description.lines())
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+
+        for entry in entries {
+            let (key, value) = match entry.split_once(':').or_else(|| entry.split_once('=')) {
+                Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                None => (String::new(), entry.to_string()),
+            };
+            let lower_key = key.to_lowercase();
+
+            if lower_key.contains("runtime") || lower_key.contains("api") || lower_key.contains("sdk") {
+                info.runtime = Some(value);
+            } else if lower_key.contains("manufacturer") || lower_key.contains("vendor") {
+                info.hmd_vendor = Some(value);
+            } else if lower_key.contains("refresh") || lower_key.contains("frequency") || lower_key.contains("hz") {
+                info.refresh_rate_hz = first_number(&value).or(info.refresh_rate_hz);
+            } else if lower_key.contains("resolution") || lower_key.contains("render") {
+                match parse_resolution(&value) {
+                    Some(resolution) => info.render_resolution = Some(resolution),
+                    None => info.other.push((key, value)),
+                }
+            } else if lower_key.contains("tracking") || lower_key.contains("space") || lower_key.contains("origin") {
+                info.tracking = Some(value);
+            } else if lower_key.contains("hmd") || lower_key.contains("headset") || lower_key.contains("model") || lower_key.contains("device") {
+                info.hmd_model = Some(value);
+            } else if let Some(runtime) = known_runtime(&value).filter(|_| key.is_empty()) {
+                //bare runtime names show up in vr_data, keep the one from the description if both exist
+                info.runtime = info.runtime.or(Some(runtime.to_string()));
+            } else {
+                info.other.push((key, value));
+            }
+        }
+
+        if info.hmd_vendor.is_none() {
+            info.hmd_vendor = info.hmd_model.as_deref().and_then(known_vendor).map(str::to_string);
+        }
+        info
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runtime.is_none() && self.hmd_model.is_none() && self.hmd_vendor.is_none()
+            && self.refresh_rate_hz.is_none() && self.render_resolution.is_none()
+            && self.tracking.is_none() && self.other.is_empty()
+    }
+
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let mut rows = vec![];
+        if let Some(runtime) = &self.runtime {
+            rows.push(("Runtime".to_string(), runtime.clone()));
+        }
+        if let Some(model) = &self.hmd_model {
+            rows.push(("HMD".to_string(), model.clone()));
+        }
+        if let Some(vendor) = &self.hmd_vendor {
+            rows.push(("Vendor".to_string(), vendor.clone()));
+        }
+        if let Some(refresh_rate) = self.refresh_rate_hz {
+            rows.push(("Refresh rate".to_string(), format!("{refresh_rate} Hz")));
+        }
+        if let Some((width, height)) = self.render_resolution {
+            rows.push(("Render resolution".to_string(), format!("{width}x{height}")));
+        }
+        if let Some(tracking) = &self.tracking {
+            rows.push(("Tracking".to_string(), tracking.clone()));
+        }
+        rows
+    }
+}
+
+fn known_runtime(value: &str) -> Option<&'static str> {
+    let lower = value.to_lowercase();
+    RUNTIMES.iter().find(|(pattern, _)| lower.contains(pattern)).map(|(_, name)| *name)
+}
+
+fn known_vendor(model: &str) -> Option<&'static str> {
+    let lower = model.to_lowercase();
+    VENDORS.iter().find(|(pattern, _)| lower.contains(pattern)).map(|(_, vendor)| *vendor)
+}